@@ -1,3 +1,5 @@
+use crate::config::{read_config, write_config, Config};
+use crate::constants::Platform;
 use crate::init::replace_string_in_file;
 use crate::style;
 use std::{collections::HashMap, env, ffi::OsStr, fs, path::Path};
@@ -6,11 +8,10 @@ use anyhow::Result;
 use dialoguer::{theme::ColorfulTheme, Input};
 use include_dir::include_dir;
 use include_dir::Dir;
-use mopro_ffi::app_config::constants::{ANDROID_BINDINGS_DIR, IOS_BINDINGS_DIR};
 use walkdir::WalkDir;
 
 use crate::{
-    build::build_project,
+    build::{build_project, BuildOptions},
     init::{adapter::Adapter, init_project},
 };
 
@@ -80,6 +81,14 @@ pub fn bindgen(
         ));
     }
 
+    // Keep the binding names configured in the current directory
+    let user_config_path = current_dir.join("Config.toml");
+    let bindings = if user_config_path.exists() {
+        read_config(&user_config_path)?.bindings
+    } else {
+        None
+    };
+
     let mut project_name = String::new();
     let mut circuit_map: HashMap<String, String> = HashMap::new();
     for entry in WalkDir::new(&absolute_circuit_dir) {
@@ -185,14 +194,30 @@ pub fn bindgen(
             "witnesscalc_adapter::build_and_link(\"./test-vectors/circom\");",
         )?;
     }
+    let config_path = project_dir.join("Config.toml");
+    let mut config = if config_path.exists() {
+        read_config(&config_path)?
+    } else {
+        Config::default()
+    };
+    config.bindings = bindings;
+    write_config(&config_path, &config)?;
+
     // Run the build command
-    build_project(arg_mode, arg_platforms, arg_architectures, None, true)?;
+    build_project(
+        arg_mode,
+        arg_platforms,
+        arg_architectures,
+        None,
+        true,
+        BuildOptions::default(),
+    )?;
 
     // Copy the bindings folder to the output directory
     fs::create_dir_all(&output_base_dir)?;
-    let ios_bindings_dir = project_dir.join(IOS_BINDINGS_DIR);
+    let ios_bindings_dir = project_dir.join(config.binding_dir(Platform::Ios));
     if ios_bindings_dir.exists() {
-        let output_ios_bindings_dir = current_dir.join(IOS_BINDINGS_DIR);
+        let output_ios_bindings_dir = current_dir.join(config.binding_dir(Platform::Ios));
         fs::create_dir_all(&output_ios_bindings_dir)?;
         fs_extra::dir::copy(
             &ios_bindings_dir,
@@ -201,7 +226,7 @@ pub fn bindgen(
         )?;
     }
 
-    let android_bindings_dir = project_dir.join(ANDROID_BINDINGS_DIR);
+    let android_bindings_dir = project_dir.join(config.binding_dir(Platform::Android));
     if android_bindings_dir.exists() {
         fs_extra::dir::copy(
             &android_bindings_dir,
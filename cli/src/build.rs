@@ -1,13 +1,27 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Confirm;
 use mopro_ffi::app_config::constants::{AndroidArch, AndroidPlatform, Arch, IosPlatform, Mode};
-use mopro_ffi::app_config::constants::{ReactNativePlatform, WebPlatform};
+use mopro_ffi::app_config::constants::{
+    CPlatform, JvmPlatform, NodePlatform, PythonPlatform, ReactNativePlatform, WebPlatform,
+};
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use mopro_ffi::app_config::android::AndroidBindingsParams;
-use mopro_ffi::app_config::build_from_str_arch;
+use mopro_ffi::app_config::c::CBindingsParams;
+use mopro_ffi::app_config::command;
+use mopro_ffi::app_config::constants::PlatformBuilder;
+use mopro_ffi::app_config::fingerprint::Fingerprint;
 use mopro_ffi::app_config::ios::IosBindingsParams;
+use mopro_ffi::app_config::jvm::JvmBindingsParams;
+use mopro_ffi::app_config::node::NodeBindingsParams;
+use mopro_ffi::app_config::plan::BuildPlan;
+use mopro_ffi::app_config::python::PythonBindingsParams;
+use mopro_ffi::app_config::size::{format_size, SizeReport};
+use mopro_ffi::app_config::web::WebBindingsParams;
+use mopro_ffi::app_config::{build_from_str_arch, plan_from_str_arch};
 
 use crate::config::read_config;
 use crate::config::write_config;
@@ -26,13 +40,35 @@ mod android_noir;
 mod mode_resolver;
 mod target_resolver;
 
+/// How `mopro build --dry-run` prints the build plans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+    Human,
+    Json,
+}
+
+/// Flags of `mopro build` that only change how the bindings are built.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuildOptions {
+    /// Print the build plans instead of building.
+    pub dry_run: Option<PlanFormat>,
+    /// Rebuild platforms even if their fingerprint did not change.
+    pub force: bool,
+    /// Number of architectures built at once, overriding `jobs` in `Config.toml`.
+    pub jobs: Option<usize>,
+    /// Number of largest symbols listed per library in the size report.
+    pub top_symbols: usize,
+}
+
 pub fn build_project(
     arg_mode: &Option<String>,
     arg_platforms: &Option<Vec<String>>,
     arg_architectures: &Option<Vec<String>>,
     auto_update_flag: Option<bool>,
     quiet: bool,
+    options: BuildOptions,
 ) -> Result<()> {
+    let dry_run = options.dry_run;
     // Detect `Cargo.toml` file before starting build process
     let current_dir = env::current_dir()?;
     let cargo_toml_path = current_dir.join("Cargo.toml");
@@ -47,21 +83,30 @@ pub fn build_project(
     // Detect `Config.toml`
     let config_path = current_dir.join("Config.toml");
 
-    // Check if the config file exists, if not create a default one
-    if !config_path.exists() {
+    // Check if the config file exists, if not create a default one.
+    // A dry run leaves the project untouched.
+    let mut config = if config_path.exists() {
+        read_config(&config_path)?
+    } else if dry_run.is_some() {
+        Config::default()
+    } else {
         let default_config = Config::default();
         write_config(&config_path, &default_config)?;
-    }
-    let mut config = read_config(&config_path)?;
+        read_config(&config_path)?
+    };
 
     // Mode selection, select `release` or `debug`
-    let mode: Mode = resolve_mode(arg_mode, &mut config)?;
-    write_config(&config_path, &config)?;
+    let mode: Mode = resolve_mode(arg_mode, &mut config, &current_dir)?;
+    if dry_run.is_none() {
+        write_config(&config_path, &config)?;
+    }
 
     // Platform selection
     let mut target_selection =
         TargetSelection::resolve_targets(arg_platforms, arg_architectures, &mut config);
-    write_config(&config_path, &config)?;
+    if dry_run.is_none() {
+        write_config(&config_path, &config)?;
+    }
 
     // Supported adapters and platforms:
     // | Platforms | Circom | Halo2 | Noir |
@@ -85,6 +130,7 @@ pub fn build_project(
                     &None,
                     auto_update_flag,
                     quiet,
+                    options,
                 );
             }
         }
@@ -103,6 +149,7 @@ pub fn build_project(
                     &None,
                     auto_update_flag,
                     quiet,
+                    options,
                 );
             }
         }
@@ -133,6 +180,7 @@ pub fn build_project(
                         &None,
                         auto_update_flag,
                         quiet,
+                        options,
                     );
                 }
             }
@@ -140,30 +188,66 @@ pub fn build_project(
     }
 
     let platforms: Vec<Platform> = target_selection.platforms().collect();
+    let jobs = options.jobs.or(config.jobs).unwrap_or(1);
+    let freshness = FreshnessCheck {
+        force: options.force,
+        extra_inputs: config.fingerprint_inputs(),
+    };
+
+    // In a dry run the builders only describe what they would run.
+    let mut plans: Option<Vec<BuildPlan>> = dry_run.map(|_| Vec::new());
 
     for selection in target_selection.iter() {
         match selection.platform() {
             Platform::Ios => {
                 let arch_strings = selection.architecture_strings();
                 let arch_refs: Vec<&String> = arch_strings.iter().collect();
-                build_from_str_arch::<IosPlatform>(
-                    mode,
+                let overrides = config.arch_overrides(Platform::Ios, &arch_refs);
+                run_builder::<IosPlatform>(
+                    &mode,
                     &current_dir,
                     arch_refs,
                     IosBindingsParams {
                         using_noir: config.adapter_contains(Adapter::Noir),
+                        names: config.ios_bindings_names(),
+                        jobs,
+                        arch_overrides: overrides,
+                        ..Default::default()
                     },
+                    &mut plans,
+                    &freshness,
                 )?;
             }
             Platform::Android => {
                 let arch_strings = selection.architecture_strings();
                 let arch_refs: Vec<&String> = arch_strings.iter().collect();
-                let params = if config.adapter_contains(Adapter::Noir) {
-                    android_noir::android_bindings_params(&current_dir, &arch_refs)?
+                let page_size = config.page_size(Platform::Android);
+                let mut params = if config.adapter_contains(Adapter::Noir) {
+                    android_noir::android_bindings_params(&current_dir, &arch_refs, page_size)?
                 } else {
                     AndroidBindingsParams::default()
                 };
-                build_from_str_arch::<AndroidPlatform>(mode, &current_dir, arch_refs, params)?;
+                params.names = config.android_bindings_names();
+                params.jobs = jobs;
+                params.split_debug_info = config.split_debug_info(Platform::Android);
+                params.page_size = page_size;
+                params.publish_aar = config.publish_aar();
+                params.gradle = config.android_gradle_versions();
+                for (arch, overrides) in config.arch_overrides(Platform::Android, &arch_refs) {
+                    params
+                        .arch_overrides
+                        .entry(arch)
+                        .or_default()
+                        .extend(&overrides);
+                }
+                run_builder::<AndroidPlatform>(
+                    &mode,
+                    &current_dir,
+                    arch_refs,
+                    params,
+                    &mut plans,
+                    &freshness,
+                )?;
             }
             Platform::Flutter => {
                 let platform_str = selection.platform().as_str();
@@ -176,37 +260,129 @@ pub fn build_project(
                     "--features",
                     "flutter",
                 ]);
-                if mode == Mode::Release {
-                    command.arg("--release");
-                }
+                command.args(mode.cargo_args());
 
-                let status = command.status()?;
-
-                if !status.success() {
-                    return Err(anyhow::anyhow!(
-                        "Output with status code {}",
-                        status.code().unwrap()
-                    ));
+                if let Some(plans) = plans.as_mut() {
+                    let mut plan = BuildPlan {
+                        platform: selection.platform().binding_name().to_string(),
+                        mode: mode.as_str().to_string(),
+                        target_archs: vec![],
+                        steps: vec![],
+                        output: current_dir.join(config.binding_dir(selection.platform())),
+                    };
+                    plan.command(
+                        "Run the project's flutter builder",
+                        &command,
+                        vec![current_dir.clone()],
+                        vec![plan.output.clone()],
+                    );
+                    plans.push(plan);
+                    continue;
                 }
+
+                command::run(&mut command).context("Failed to build the Flutter bindings")?;
             }
             Platform::ReactNative => {
                 let arch_strings = selection.architecture_strings();
                 let arch_refs: Vec<&String> = arch_strings.iter().collect();
-                build_from_str_arch::<ReactNativePlatform>(mode, &current_dir, arch_refs, ())?;
+                run_builder::<ReactNativePlatform>(
+                    &mode,
+                    &current_dir,
+                    arch_refs,
+                    config.platform_overrides(Platform::ReactNative),
+                    &mut plans,
+                    &freshness,
+                )?;
             }
             Platform::Web => {
-                build_from_str_arch::<WebPlatform>(
-                    mode,
+                let arch = "wasm32-unknown-unknown".to_string();
+                run_builder::<WebPlatform>(
+                    &mode,
+                    &current_dir,
+                    vec![&arch],
+                    WebBindingsParams {
+                        bindings_dir: config.binding_dir(Platform::Web),
+                        arch_overrides: config.arch_overrides(Platform::Web, &[&arch]),
+                    },
+                    &mut plans,
+                    &freshness,
+                )?;
+            }
+            Platform::Python => {
+                let arch_strings = selection.architecture_strings();
+                let arch_refs: Vec<&String> = arch_strings.iter().collect();
+                let overrides = config.arch_overrides(Platform::Python, &arch_refs);
+                run_builder::<PythonPlatform>(
+                    &mode,
+                    &current_dir,
+                    arch_refs,
+                    PythonBindingsParams {
+                        arch_overrides: overrides,
+                        ..config.python_bindings_params()
+                    },
+                    &mut plans,
+                    &freshness,
+                )?;
+            }
+            Platform::Jvm => {
+                let arch_strings = selection.architecture_strings();
+                let arch_refs: Vec<&String> = arch_strings.iter().collect();
+                let overrides = config.arch_overrides(Platform::Jvm, &arch_refs);
+                run_builder::<JvmPlatform>(
+                    &mode,
+                    &current_dir,
+                    arch_refs,
+                    JvmBindingsParams {
+                        names: config.jvm_bindings_names(),
+                        arch_overrides: overrides,
+                    },
+                    &mut plans,
+                    &freshness,
+                )?;
+            }
+            Platform::Node => {
+                let arch_strings = selection.architecture_strings();
+                let arch_refs: Vec<&String> = arch_strings.iter().collect();
+                let overrides = config.arch_overrides(Platform::Node, &arch_refs);
+                run_builder::<NodePlatform>(
+                    &mode,
                     &current_dir,
-                    vec![&"wasm32-unknown-unknown".to_string()],
-                    (),
+                    arch_refs,
+                    NodeBindingsParams {
+                        arch_overrides: overrides,
+                        ..config.node_bindings_params()
+                    },
+                    &mut plans,
+                    &freshness,
+                )?;
+            }
+            Platform::C => {
+                let arch_strings = selection.architecture_strings();
+                let arch_refs: Vec<&String> = arch_strings.iter().collect();
+                let overrides = config.arch_overrides(Platform::C, &arch_refs);
+                run_builder::<CPlatform>(
+                    &mode,
+                    &current_dir,
+                    arch_refs,
+                    CBindingsParams {
+                        arch_overrides: overrides,
+                        ..config.c_bindings_params()
+                    },
+                    &mut plans,
+                    &freshness,
                 )?;
             }
         }
     }
 
+    if let (Some(format), Some(plans)) = (dry_run, plans) {
+        return print_build_plans(format, &plans);
+    }
+
+    report_sizes(&current_dir, &config, &platforms, options.top_symbols)?;
+
     if !quiet {
-        print_binding_message(&platforms)?;
+        print_binding_message(&config, &platforms)?;
     }
     handle_auto_update(&config_path, &mut config, auto_update_flag)?;
     print_build_success_message();
@@ -214,12 +390,111 @@ pub fn build_project(
     Ok(())
 }
 
-fn print_binding_message(platforms: &[Platform]) -> anyhow::Result<()> {
+/// When [`run_builder`] skips a platform whose inputs did not change.
+struct FreshnessCheck {
+    /// Rebuild even if the fingerprints match.
+    force: bool,
+    /// `fingerprint_inputs` of `Config.toml`.
+    extra_inputs: Vec<PathBuf>,
+}
+
+/// Build the bindings with `Builder`, or only record its plan in a dry run.
+///
+/// Unless `force` is set, the build is skipped when the fingerprints of its
+/// archs match the ones recorded by the last build. The fingerprints computed
+/// before the build are recorded once it succeeds.
+fn run_builder<Builder: PlatformBuilder>(
+    mode: &Mode,
+    project_dir: &Path,
+    target_archs: Vec<&String>,
+    params: Builder::Params,
+    plans: &mut Option<Vec<BuildPlan>>,
+    freshness: &FreshnessCheck,
+) -> Result<()> {
+    let plan =
+        plan_from_str_arch::<Builder>(mode.clone(), project_dir, target_archs.clone(), &params)?;
+    if let Some(plans) = plans {
+        plans.push(plan);
+        return Ok(());
+    }
+
+    let fingerprint = Fingerprint::compute(project_dir, &plan, &freshness.extra_inputs)?;
+    if !freshness.force && fingerprint.is_fresh(&plan.output) {
+        style::print_yellow(format!(
+            "{} bindings are up to date, skipping. Use --force to rebuild them.",
+            plan.platform
+        ));
+        return Ok(());
+    }
+    build_from_str_arch::<Builder>(mode.clone(), project_dir, target_archs, params)?;
+    fingerprint.record()
+}
+
+/// Print the size report of each platform, save it to
+/// `build/size-report/<platform>.json` and fail if a platform is over its
+/// `[size_budget]`.
+fn report_sizes(
+    project_dir: &Path,
+    config: &Config,
+    platforms: &[Platform],
+    top_symbols: usize,
+) -> Result<()> {
+    let report_dir = project_dir.join("build").join("size-report");
+    let mut over_budget = Vec::new();
+    for platform in platforms {
+        let bindings_dir = project_dir.join(config.binding_dir(*platform));
+        if !bindings_dir.exists() {
+            continue;
+        }
+        let report = SizeReport::collect(platform.as_str(), &bindings_dir, top_symbols)?;
+        println!("{report}\n");
+
+        fs::create_dir_all(&report_dir)?;
+        fs::write(
+            report_dir.join(format!("{}.json", platform.as_str())),
+            serde_json::to_string_pretty(&report)?,
+        )?;
+
+        if let Some(budget) = config.size_budget(*platform)? {
+            if report.total > budget {
+                over_budget.push(format!(
+                    "{} is {}, over its budget of {}",
+                    platform.as_str(),
+                    format_size(report.total),
+                    format_size(budget)
+                ));
+            }
+        }
+    }
+
+    if !over_budget.is_empty() {
+        anyhow::bail!("Size budget exceeded: {}", over_budget.join("; "));
+    }
+    Ok(())
+}
+
+fn print_build_plans(format: PlanFormat, plans: &[BuildPlan]) -> Result<()> {
+    match format {
+        PlanFormat::Json => println!("{}", serde_json::to_string_pretty(plans)?),
+        PlanFormat::Human => {
+            for plan in plans {
+                println!("{plan}\n");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_binding_message(config: &Config, platforms: &[Platform]) -> anyhow::Result<()> {
     let current_dir = env::current_dir()?;
     print_green_bold("✨ Bindings Built Successfully! ✨".to_string());
     println!("The Mopro bindings have been successfully generated and are available in the following directories:\n");
     for platform in platforms {
-        let text = format!("- {}/{}", current_dir.display(), platform.binding_dir());
+        let text = format!(
+            "- {}/{}",
+            current_dir.display(),
+            config.binding_dir(*platform)
+        );
         println!("{}", blue_bold(text.to_string()));
     }
     Ok(())
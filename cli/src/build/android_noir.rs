@@ -9,7 +9,8 @@
 //! resolves (the NDK's `std::__ndk1` is ABI-incompatible).
 
 use anyhow::Context;
-use mopro_ffi::app_config::android::{AndroidBindingsParams, ArchBuildConfig};
+use mopro_ffi::app_config::android::{max_page_size_flag, AndroidBindingsParams};
+use mopro_ffi::app_config::ArchBuildConfig;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -20,6 +21,7 @@ use std::process::Command;
 pub fn android_bindings_params(
     project_dir: &Path,
     arch_strs: &[&String],
+    page_size: Option<u64>,
 ) -> anyhow::Result<AndroidBindingsParams> {
     let mut params = AndroidBindingsParams::default();
 
@@ -41,7 +43,7 @@ pub fn android_bindings_params(
 
     for (triple, bb_arch) in &bb_targets {
         let bb_lib_dir = download_barretenberg_android_lib(bb_arch, &version, &build_dir)?;
-        let linker_flag = zig_linker_flag(triple, &build_dir)?;
+        let linker_flag = zig_linker_flag(triple, &build_dir, page_size)?;
         params.arch_overrides.insert(
             triple.clone(),
             ArchBuildConfig {
@@ -50,6 +52,7 @@ pub fn android_bindings_params(
                     bb_lib_dir.to_string_lossy().into_owned(),
                 )],
                 extra_rustflags: vec![linker_flag],
+                ..Default::default()
             },
         );
     }
@@ -147,8 +150,13 @@ fn ensure_zig_available() -> anyhow::Result<()> {
 
 /// Write a Zig-cc linker wrapper for `triple` and return the `-Clinker=<wrapper>`
 /// flag. Zig provides the barretenberg prebuilt's `std::__1` libc++ symbols; only
-/// the linker is overridden, so the NDK still compiles everything else.
-fn zig_linker_flag(triple: &str, build_dir: &Path) -> anyhow::Result<String> {
+/// the linker is overridden, so the NDK still compiles everything else. A
+/// `page_size` is passed on to Zig's lld, whose default alignment is 4 KB.
+fn zig_linker_flag(
+    triple: &str,
+    build_dir: &Path,
+    page_size: Option<u64>,
+) -> anyhow::Result<String> {
     let ndk = android_ndk_home()?;
     let host = ndk_host_tag();
     let sysroot = ndk
@@ -184,11 +192,14 @@ fn zig_linker_flag(triple: &str, build_dir: &Path) -> anyhow::Result<String> {
     // `zig cc` bakes in Zig's static `__1` libc++ (resolving barretenberg's `-lc++`);
     // the trailing `-lc++_shared` keeps the NDK's `__ndk1` libc++ for other C++.
     let wrapper = build_dir_abs.join(format!("zig-android-cc-{triple}.sh"));
+    let page_size_flag = page_size
+        .map(|page_size| format!("{} ", max_page_size_flag(page_size)))
+        .unwrap_or_default();
     fs::write(
         &wrapper,
         format!(
             "#!/bin/sh\nset -e\nexport ZIG_LIBC=\"{libc}\"\nexec zig cc -target {triple} \
-             -L \"{crt}\" -L \"{tl}\" \"$@\" -lc++_shared\n",
+             {page_size_flag}-L \"{crt}\" -L \"{tl}\" \"$@\" -lc++_shared\n",
             libc = libc_conf.display(),
             triple = triple,
             crt = crt_dir.display(),
@@ -2,46 +2,84 @@ use crate::config::Config;
 use crate::style;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Select;
-use mopro_ffi::app_config::constants::Mode;
+use mopro_ffi::app_config::constants::{Mode, MODE_PROFILE_PREFIX};
+use std::fs;
+use std::path::Path;
 
-pub(super) fn resolve_mode(arg_mode: &Option<String>, config: &mut Config) -> anyhow::Result<Mode> {
+/// Profiles cargo defines itself, which are not offered as build modes.
+const BUILTIN_PROFILES: [&str; 4] = ["dev", "release", "test", "bench"];
+
+pub(super) fn resolve_mode(
+    arg_mode: &Option<String>,
+    config: &mut Config,
+    project_dir: &Path,
+) -> anyhow::Result<Mode> {
+    let options = mode_options(project_dir)?;
     match arg_mode.as_deref() {
-        Some(raw_mode) => match Mode::idx(raw_mode) {
-            Some(idx) => {
-                let mode = Mode::from_idx(idx);
-                config.build_mode = Some(mode.as_str().to_string());
-                Ok(mode)
-            }
-            None => {
-                style::print_yellow(
-                    "Invalid mode selected. Please choose a valid mode (e.g., 'release' or 'debug')."
-                        .to_string(),
-                );
-                select_mode(config)
+        Some(raw_mode) => {
+            let raw_mode = raw_mode
+                .strip_prefix(MODE_PROFILE_PREFIX)
+                .unwrap_or(raw_mode);
+            match options.iter().find(|option| *option == raw_mode) {
+                Some(option) => {
+                    let mode = mode_from_option(option);
+                    config.build_mode = Some(mode.as_str().to_string());
+                    Ok(mode)
+                }
+                None => {
+                    style::print_yellow(format!(
+                        "Invalid mode selected. Please choose one of: {}.",
+                        options.join(", ")
+                    ));
+                    select_mode(config, &options)
+                }
             }
-        },
-        None => select_mode(config),
+        }
+        None => select_mode(config, &options),
     }
 }
 
-fn select_mode(config: &mut Config) -> anyhow::Result<Mode> {
+fn select_mode(config: &mut Config, options: &[String]) -> anyhow::Result<Mode> {
     let theme = ColorfulTheme::default();
     let mut selection = Select::with_theme(&theme);
-    let options = Mode::all_strings();
 
     if let Some(build_mode) = config.build_mode.as_ref() {
-        if let Some(idx) = Mode::idx(build_mode.as_str()) {
+        if let Some(idx) = options.iter().position(|option| option == build_mode) {
             selection.default(idx);
         }
     }
 
     let idx = selection
         .with_prompt("Build mode")
-        .items(options.as_ref())
+        .items(options)
         .interact()?;
 
-    let mode = Mode::from_idx(idx);
+    let mode = mode_from_option(&options[idx]);
     config.build_mode = Some(mode.as_str().to_string());
 
     Ok(mode)
 }
+
+/// The options are `debug`, `release` or the name of a custom profile found in
+/// `Cargo.toml`.
+fn mode_from_option(option: &str) -> Mode {
+    Mode::parse_from_str(option).unwrap_or_else(|_| Mode::Profile(option.to_string()))
+}
+
+/// `debug`, `release` and the custom `[profile.<name>]` sections of the
+/// project's `Cargo.toml`.
+fn mode_options(project_dir: &Path) -> anyhow::Result<Vec<String>> {
+    let mut options: Vec<String> = Mode::all_strings().into_iter().map(String::from).collect();
+
+    let cargo_toml = fs::read_to_string(project_dir.join("Cargo.toml"))?;
+    let cargo_toml: toml::Table = toml::from_str(&cargo_toml)?;
+    if let Some(profiles) = cargo_toml.get("profile").and_then(|p| p.as_table()) {
+        options.extend(
+            profiles
+                .keys()
+                .filter(|name| !BUILTIN_PROFILES.contains(&name.as_str()))
+                .cloned(),
+        );
+    }
+    Ok(options)
+}
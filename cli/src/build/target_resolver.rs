@@ -1,7 +1,9 @@
 use std::collections::HashSet;
 use std::sync::Once;
 
-use mopro_ffi::app_config::constants::{AndroidArch, Arch, IosArch, ReactNativeArch};
+use mopro_ffi::app_config::constants::{
+    AndroidArch, Arch, CArch, HostArch, IosArch, JvmArch, NodeArch, PythonArch, ReactNativeArch,
+};
 
 use crate::{config::Config, constants::Platform, select::multi_select, style};
 
@@ -23,6 +25,10 @@ pub(super) enum PlatformArchitectures {
     Flutter,
     ReactNative(Vec<ReactNativeArch>),
     Web,
+    Python(PythonArch),
+    Jvm(Vec<JvmArch>),
+    Node(NodeArch),
+    C(Vec<CArch>),
 }
 
 impl TargetSelection {
@@ -107,6 +113,14 @@ impl TargetSelection {
                     archs.retain(|a| a.as_str() != arch);
                 }
                 PlatformArchitectures::Web => {}
+                PlatformArchitectures::Python(_) => {}
+                PlatformArchitectures::Jvm(archs) => {
+                    archs.retain(|a| a.as_str() != arch);
+                }
+                PlatformArchitectures::Node(_) => {}
+                PlatformArchitectures::C(archs) => {
+                    archs.retain(|a| a.as_str() != arch);
+                }
             }
         }
         self.selections
@@ -116,6 +130,10 @@ impl TargetSelection {
                 PlatformArchitectures::Flutter => true,
                 PlatformArchitectures::ReactNative(archs) => !archs.is_empty(),
                 PlatformArchitectures::Web => true,
+                PlatformArchitectures::Python(_) => true,
+                PlatformArchitectures::Jvm(archs) => !archs.is_empty(),
+                PlatformArchitectures::Node(_) => true,
+                PlatformArchitectures::C(archs) => !archs.is_empty(),
             });
     }
 
@@ -138,6 +156,14 @@ impl TargetSelection {
         config.react_native = self
             .architecture_strings_for(Platform::ReactNative)
             .map(|archs| archs.into_iter().collect());
+
+        config.jvm = self
+            .architecture_strings_for(Platform::Jvm)
+            .map(|archs| archs.into_iter().collect());
+
+        config.c = self
+            .architecture_strings_for(Platform::C)
+            .map(|archs| archs.into_iter().collect());
     }
 }
 
@@ -165,6 +191,14 @@ impl PlatformArchitectures {
                 archs.iter().map(|arch| arch.as_str().to_string()).collect()
             }
             PlatformArchitectures::Web => Vec::new(),
+            PlatformArchitectures::Python(arch) => vec![arch.as_str().to_string()],
+            PlatformArchitectures::Jvm(archs) => {
+                archs.iter().map(|arch| arch.as_str().to_string()).collect()
+            }
+            PlatformArchitectures::Node(arch) => vec![arch.as_str().to_string()],
+            PlatformArchitectures::C(archs) => {
+                archs.iter().map(|arch| arch.as_str().to_string()).collect()
+            }
         }
     }
 
@@ -181,6 +215,14 @@ impl PlatformArchitectures {
                 archs.iter().any(|candidate| candidate.as_str() == arch)
             }
             PlatformArchitectures::Web => false,
+            PlatformArchitectures::Python(candidate) => candidate.as_str() == arch,
+            PlatformArchitectures::Jvm(archs) => {
+                archs.iter().any(|candidate| candidate.as_str() == arch)
+            }
+            PlatformArchitectures::Node(candidate) => candidate.as_str() == arch,
+            PlatformArchitectures::C(archs) => {
+                archs.iter().any(|candidate| candidate.as_str() == arch)
+            }
         }
     }
 }
@@ -189,6 +231,8 @@ struct ArgArch {
     ios: Vec<IosArch>,
     android: Vec<AndroidArch>,
     react_native: Vec<ReactNativeArch>,
+    jvm: Vec<JvmArch>,
+    c: Vec<CArch>,
     invalid: Vec<String>,
 }
 
@@ -224,6 +268,8 @@ fn resolve_architectures(
         ios,
         android,
         react_native,
+        jvm,
+        c,
         ..
     } = arg_arch;
 
@@ -292,6 +338,72 @@ fn resolve_architectures(
         });
     }
 
+    // Python bindings are always built for the host machine.
+    if platforms.contains(&Platform::Python) {
+        if let Some(arch) = PythonArch::host() {
+            selections.push(PlatformSelection {
+                platform: Platform::Python,
+                architectures: PlatformArchitectures::Python(arch),
+            });
+        } else {
+            style::print_yellow(format!(
+                "Python bindings are not supported on this host ({}-{}), skipping.",
+                std::env::consts::ARCH,
+                std::env::consts::OS
+            ));
+        }
+    }
+
+    // Node bindings are always built for the host machine.
+    if platforms.contains(&Platform::Node) {
+        if let Some(arch) = NodeArch::host() {
+            selections.push(PlatformSelection {
+                platform: Platform::Node,
+                architectures: PlatformArchitectures::Node(arch),
+            });
+        } else {
+            style::print_yellow(format!(
+                "Node bindings are not supported on this host ({}-{}), skipping.",
+                std::env::consts::ARCH,
+                std::env::consts::OS
+            ));
+        }
+    }
+
+    if platforms.contains(&Platform::Jvm) {
+        let jvm_platform_arch = if !jvm.is_empty() {
+            PlatformArchitectures::Jvm(jvm)
+        } else {
+            PlatformArchitectures::Jvm(prompt_architectures(
+                Platform::Jvm,
+                config.jvm.as_ref(),
+                JvmArch::all_strings(),
+            ))
+        };
+
+        selections.push(PlatformSelection {
+            platform: Platform::Jvm,
+            architectures: jvm_platform_arch,
+        });
+    }
+
+    if platforms.contains(&Platform::C) {
+        let c_platform_arch = if !c.is_empty() {
+            PlatformArchitectures::C(c)
+        } else {
+            PlatformArchitectures::C(prompt_architectures(
+                Platform::C,
+                config.c.as_ref(),
+                CArch::all_strings(),
+            ))
+        };
+
+        selections.push(PlatformSelection {
+            platform: Platform::C,
+            architectures: c_platform_arch,
+        });
+    }
+
     selections
 }
 
@@ -350,11 +462,15 @@ fn parse_architectures(platforms: &[Platform], arg_architectures: &Option<Vec<St
     let allow_ios = platforms.contains(&Platform::Ios);
     let allow_android = platforms.contains(&Platform::Android);
     let allow_react_native = platforms.contains(&Platform::ReactNative);
+    let allow_jvm = platforms.contains(&Platform::Jvm);
+    let allow_c = platforms.contains(&Platform::C);
 
     let mut invalid = Vec::new();
     let mut ios_arch = Vec::new();
     let mut android_arch = Vec::new();
     let mut react_native_arch = Vec::new();
+    let mut jvm_arch = Vec::new();
+    let mut c_arch = Vec::new();
 
     if let Some(values) = arg_architectures {
         for value in values {
@@ -379,6 +495,20 @@ fn parse_architectures(platforms: &[Platform], arg_architectures: &Option<Vec<St
                 }
             }
 
+            if allow_jvm {
+                if let Some(arch) = parse_jvm_arch(value) {
+                    jvm_arch.push(arch);
+                    continue;
+                }
+            }
+
+            if allow_c {
+                if let Some(arch) = parse_c_arch(value) {
+                    c_arch.push(arch);
+                    continue;
+                }
+            }
+
             invalid.push(value.clone());
         }
     }
@@ -387,6 +517,8 @@ fn parse_architectures(platforms: &[Platform], arg_architectures: &Option<Vec<St
         ios: ios_arch,
         android: android_arch,
         react_native: react_native_arch,
+        jvm: jvm_arch,
+        c: c_arch,
         invalid,
     }
 }
@@ -412,6 +544,17 @@ fn parse_react_native_arch(value: &str) -> Option<ReactNativeArch> {
         .map(ReactNativeArch::parse_from_str)
 }
 
+fn parse_jvm_arch(value: &str) -> Option<JvmArch> {
+    HostArch::parse(value).map(JvmArch)
+}
+
+fn parse_c_arch(value: &str) -> Option<CArch> {
+    CArch::all_strings()
+        .into_iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(value))
+        .map(CArch::parse_from_str)
+}
+
 fn print_architecture_hint() {
     static PRINTED: Once = Once::new();
     PRINTED.call_once(|| {
@@ -1,11 +1,19 @@
-use anyhow::Result;
-use std::collections::HashSet;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
 use std::io::Read;
 use std::io::Write;
 use std::path::PathBuf;
 
+use crate::constants::Platform;
 use crate::init::adapter::Adapter;
+use mopro_ffi::app_config::android::{AndroidBindingsNames, AndroidGradleVersions};
+use mopro_ffi::app_config::c::CBindingsParams;
+use mopro_ffi::app_config::ios::IosBindingsNames;
+use mopro_ffi::app_config::jvm::JvmBindingsNames;
+use mopro_ffi::app_config::node::{NodeBindgen, NodeBindingsParams};
+use mopro_ffi::app_config::python::PythonBindingsParams;
+use mopro_ffi::app_config::{ArchBuildConfig, ArchOverrides};
 
 // Storing user selections while iterating with mopro cli
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -16,9 +24,26 @@ pub struct Config {
     pub(crate) ios: Option<HashSet<String>>,
     pub(crate) android: Option<HashSet<String>>,
     pub(crate) react_native: Option<HashSet<String>>,
+    pub(crate) jvm: Option<HashSet<String>>,
+    pub(crate) c: Option<HashSet<String>>,
     pub(crate) update: Option<UpdateConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) auto_update: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bindings: Option<BindingsConfig>,
+    /// Number of architectures of a platform built at once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) jobs: Option<usize>,
+    /// Cargo settings per platform, keyed like `--platforms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) platform: Option<BTreeMap<String, PlatformBuildConfig>>,
+    /// Maximum total library size per platform, e.g. `ios = "60MB"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) size_budget: Option<BTreeMap<String, String>>,
+    /// Files and directories outside the project sources, such as circuits,
+    /// whose changes rebuild every platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) fingerprint_inputs: Option<Vec<String>>,
 }
 
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
@@ -28,6 +53,84 @@ pub struct UpdateConfig {
     pub(crate) react_native_dest: Option<String>,
 }
 
+/// Overrides for the names of the generated bindings. Unset fields keep the
+/// default `MoproiOSBindings`, `uniffi.mopro`, ... names.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub struct BindingsConfig {
+    pub(crate) ios_dir: Option<String>,
+    pub(crate) swift_file: Option<String>,
+    pub(crate) xcframework: Option<String>,
+    pub(crate) android_dir: Option<String>,
+    pub(crate) kotlin_package: Option<String>,
+    pub(crate) kotlin_file: Option<String>,
+    pub(crate) web_dir: Option<String>,
+    pub(crate) python_dir: Option<String>,
+    pub(crate) python_package: Option<String>,
+    pub(crate) jvm_dir: Option<String>,
+    pub(crate) node_dir: Option<String>,
+    pub(crate) node_package: Option<String>,
+    pub(crate) c_dir: Option<String>,
+    pub(crate) c_header: Option<String>,
+}
+
+/// Cargo features and flags for the builds of one target.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub struct CargoBuildConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) features: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) no_default_features: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rustflags: Option<Vec<String>>,
+}
+
+/// A `[platform.<name>]` section: settings for all its architectures, and
+/// additions for single ones under `[platform.<name>.arch.<triple>]`.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub struct PlatformBuildConfig {
+    #[serde(flatten)]
+    pub(crate) cargo: CargoBuildConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) arch: Option<BTreeMap<String, CargoBuildConfig>>,
+    /// Strip the shipped libraries and keep their debug info apart (Android
+    /// only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) split_debug_info: Option<bool>,
+    /// Page size in bytes the libraries are aligned to (Android only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) page_size: Option<u64>,
+    /// Publish the `.aar` of the bindings module to its `maven/` repository
+    /// (Android only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) publish_aar: Option<bool>,
+    /// Android Gradle Plugin version of the library module (Android only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) agp_version: Option<String>,
+    /// Kotlin Gradle plugin version of the library module (Android only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) kotlin_version: Option<String>,
+    /// `compileSdk` of the library module (Android only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) compile_sdk: Option<u32>,
+    /// Crate name or path of the bindings generator (Node only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bindgen: Option<String>,
+    /// Version of the `bindgen` crate to install (Node only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bindgen_version: Option<String>,
+}
+
+impl CargoBuildConfig {
+    fn to_arch_build_config(&self) -> ArchBuildConfig {
+        ArchBuildConfig {
+            features: self.features.clone().unwrap_or_default(),
+            no_default_features: self.no_default_features.unwrap_or(false),
+            extra_env: vec![],
+            extra_rustflags: self.rustflags.clone().unwrap_or_default(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -37,8 +140,15 @@ impl Default for Config {
             ios: Some(HashSet::new()),
             android: Some(HashSet::new()),
             react_native: Some(HashSet::new()),
+            jvm: Some(HashSet::new()),
+            c: Some(HashSet::new()),
             update: Some(UpdateConfig::default()),
             auto_update: Some(false),
+            bindings: None,
+            jobs: None,
+            platform: None,
+            size_budget: None,
+            fingerprint_inputs: None,
         }
     }
 }
@@ -51,6 +161,218 @@ impl Config {
             false
         }
     }
+
+    pub fn ios_bindings_names(&self) -> IosBindingsNames {
+        let mut names = IosBindingsNames::default();
+        if let Some(bindings) = &self.bindings {
+            override_name(&mut names.bindings_dir, &bindings.ios_dir);
+            override_name(&mut names.swift_file, &bindings.swift_file);
+            override_name(&mut names.xcframework, &bindings.xcframework);
+        }
+        names
+    }
+
+    pub fn android_bindings_names(&self) -> AndroidBindingsNames {
+        let mut names = AndroidBindingsNames::default();
+        if let Some(bindings) = &self.bindings {
+            override_name(&mut names.bindings_dir, &bindings.android_dir);
+            override_name(&mut names.package_name, &bindings.kotlin_package);
+            override_name(&mut names.kt_file, &bindings.kotlin_file);
+        }
+        names
+    }
+
+    /// The JVM bindings share the Kotlin package and file names of Android.
+    pub fn jvm_bindings_names(&self) -> JvmBindingsNames {
+        let mut names = JvmBindingsNames::default();
+        if let Some(bindings) = &self.bindings {
+            override_name(&mut names.bindings_dir, &bindings.jvm_dir);
+            override_name(&mut names.package_name, &bindings.kotlin_package);
+            override_name(&mut names.kt_file, &bindings.kotlin_file);
+        }
+        names
+    }
+
+    pub fn python_bindings_params(&self) -> PythonBindingsParams {
+        let mut params = PythonBindingsParams {
+            bindings_dir: self.binding_dir(Platform::Python),
+            ..Default::default()
+        };
+        if let Some(bindings) = &self.bindings {
+            override_name(&mut params.package_name, &bindings.python_package);
+        }
+        params
+    }
+
+    pub fn node_bindings_params(&self) -> NodeBindingsParams {
+        NodeBindingsParams {
+            bindings_dir: self.binding_dir(Platform::Node),
+            package_name: self.bindings.as_ref().and_then(|b| b.node_package.clone()),
+            bindgen: self.node_bindgen(),
+            ..Default::default()
+        }
+    }
+
+    pub fn c_bindings_params(&self) -> CBindingsParams {
+        let mut params = CBindingsParams {
+            bindings_dir: self.binding_dir(Platform::C),
+            ..Default::default()
+        };
+        if let Some(bindings) = &self.bindings {
+            override_name(&mut params.header_file, &bindings.c_header);
+        }
+        params
+    }
+
+    fn platform_build_config(&self, platform: Platform) -> Option<&PlatformBuildConfig> {
+        self.platform.as_ref()?.get(platform.as_str())
+    }
+
+    /// The settings of `[platform.<platform>]`, for platforms built in a
+    /// single run for all their architectures.
+    pub fn platform_overrides(&self, platform: Platform) -> ArchBuildConfig {
+        self.platform_build_config(platform)
+            .map(|config| config.cargo.to_arch_build_config())
+            .unwrap_or_default()
+    }
+
+    /// Whether `[platform.<platform>]` sets `split_debug_info`.
+    pub fn split_debug_info(&self, platform: Platform) -> bool {
+        self.platform_build_config(platform)
+            .and_then(|config| config.split_debug_info)
+            .unwrap_or(false)
+    }
+
+    /// Whether `[platform.android]` sets `publish_aar`.
+    pub fn publish_aar(&self) -> bool {
+        self.platform_build_config(Platform::Android)
+            .and_then(|config| config.publish_aar)
+            .unwrap_or(false)
+    }
+
+    /// The versions set by `agp_version`, `kotlin_version` and `compile_sdk`
+    /// of `[platform.android]`.
+    pub fn android_gradle_versions(&self) -> AndroidGradleVersions {
+        let mut versions = AndroidGradleVersions::default();
+        if let Some(config) = self.platform_build_config(Platform::Android) {
+            if let Some(agp) = &config.agp_version {
+                versions.agp = agp.clone();
+            }
+            if let Some(kotlin) = &config.kotlin_version {
+                versions.kotlin = kotlin.clone();
+            }
+            if let Some(compile_sdk) = config.compile_sdk {
+                versions.compile_sdk = compile_sdk;
+            }
+        }
+        versions
+    }
+
+    /// The `fingerprint_inputs`, relative to the project.
+    pub fn fingerprint_inputs(&self) -> Vec<PathBuf> {
+        self.fingerprint_inputs
+            .iter()
+            .flatten()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// The `page_size` of `[platform.<platform>]`.
+    pub fn page_size(&self, platform: Platform) -> Option<u64> {
+        self.platform_build_config(platform)?.page_size
+    }
+
+    /// The generator set by `bindgen` and `bindgen_version` of
+    /// `[platform.node]`.
+    pub fn node_bindgen(&self) -> NodeBindgen {
+        let mut bindgen = NodeBindgen::default();
+        if let Some(config) = self.platform_build_config(Platform::Node) {
+            if let Some(command) = &config.bindgen {
+                bindgen.command = command.clone();
+            }
+            if let Some(version) = &config.bindgen_version {
+                bindgen.version = version.clone();
+            }
+        }
+        bindgen
+    }
+
+    /// The settings of `[platform.<platform>]` and of its `arch.<triple>`
+    /// sections, for each of `archs`.
+    pub fn arch_overrides(&self, platform: Platform, archs: &[&String]) -> ArchOverrides {
+        let Some(config) = self.platform_build_config(platform) else {
+            return ArchOverrides::default();
+        };
+        archs
+            .iter()
+            .map(|arch| {
+                let mut overrides = config.cargo.to_arch_build_config();
+                if let Some(arch_config) = config.arch.as_ref().and_then(|a| a.get(*arch)) {
+                    overrides.extend(&arch_config.to_arch_build_config());
+                }
+                (arch.to_string(), overrides)
+            })
+            .collect()
+    }
+
+    /// The `[size_budget]` of `platform`, in bytes.
+    pub fn size_budget(&self, platform: Platform) -> Result<Option<u64>> {
+        let Some(budget) = self
+            .size_budget
+            .as_ref()
+            .and_then(|budgets| budgets.get(platform.as_str()))
+        else {
+            return Ok(None);
+        };
+        parse_size(budget)
+            .map(Some)
+            .with_context(|| format!("Invalid size budget for {}: {budget}", platform.as_str()))
+    }
+
+    /// Directory the bindings of `platform` are generated into.
+    pub fn binding_dir(&self, platform: Platform) -> String {
+        let configured = self.bindings.as_ref().and_then(|b| match platform {
+            Platform::Ios => b.ios_dir.clone(),
+            Platform::Android => b.android_dir.clone(),
+            Platform::Web => b.web_dir.clone(),
+            Platform::Python => b.python_dir.clone(),
+            Platform::Jvm => b.jvm_dir.clone(),
+            Platform::Node => b.node_dir.clone(),
+            Platform::C => b.c_dir.clone(),
+            // The Flutter and React Native directories are also their package
+            // names, so they keep the defaults.
+            Platform::Flutter | Platform::ReactNative => None,
+        });
+        configured.unwrap_or_else(|| platform.binding_dir().to_string())
+    }
+}
+
+fn override_name(name: &mut String, configured: &Option<String>) {
+    if let Some(configured) = configured {
+        *name = configured.clone();
+    }
+}
+
+/// Parse sizes like `800KB`, `50MB` or `1.5 GiB`. `KB`, `MB` and `GB` are
+/// powers of 1000, `KiB`, `MiB` and `GiB` powers of 1024.
+fn parse_size(size: &str) -> Result<u64> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000 * 1000,
+        "gb" => 1000 * 1000 * 1000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        unit => anyhow::bail!("unknown unit `{unit}`"),
+    };
+    let number: f64 = number.parse()?;
+    Ok((number * multiplier as f64) as u64)
 }
 
 pub fn read_config(file_path: &PathBuf) -> Result<Config> {
@@ -67,3 +389,37 @@ pub fn write_config(file_path: &PathBuf, config: &Config) -> Result<()> {
     file.write_all(toml_string.as_bytes())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("800KB").unwrap(), 800_000);
+        assert_eq!(parse_size("50MB").unwrap(), 50_000_000);
+        assert_eq!(parse_size("1.5 GiB").unwrap(), 3 << 29);
+        assert_eq!(parse_size(" 2 mib ").unwrap(), 2 << 20);
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert_eq!(parse_size("12B").unwrap(), 12);
+    }
+
+    #[test]
+    fn rejects_bad_sizes() {
+        let error = parse_size("5 TB").unwrap_err();
+        assert_eq!(error.to_string(), "unknown unit `tb`");
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("1.2.3MB").is_err());
+    }
+
+    #[test]
+    fn reads_android_gradle_versions() {
+        let config: Config =
+            toml::from_str("[platform.android]\nagp_version = \"8.7.3\"\ncompile_sdk = 35\n")
+                .unwrap();
+        let versions = config.android_gradle_versions();
+        assert_eq!(versions.agp, "8.7.3");
+        assert_eq!(versions.kotlin, AndroidGradleVersions::default().kotlin);
+        assert_eq!(versions.compile_sdk, 35);
+    }
+}
@@ -1,6 +1,6 @@
 use mopro_ffi::app_config::constants::{
-    ANDROID_BINDINGS_DIR, FLUTTER_BINDINGS_DIR, IOS_BINDINGS_DIR, REACT_NATIVE_BINDINGS_DIR,
-    WEB_BINDINGS_DIR,
+    ANDROID_BINDINGS_DIR, C_BINDINGS_DIR, FLUTTER_BINDINGS_DIR, IOS_BINDINGS_DIR, JVM_BINDINGS_DIR,
+    NODE_BINDINGS_DIR, PYTHON_BINDINGS_DIR, REACT_NATIVE_BINDINGS_DIR, WEB_BINDINGS_DIR,
 };
 
 //
@@ -13,6 +13,10 @@ pub enum Platform {
     Flutter,
     ReactNative,
     Web,
+    Python,
+    Jvm,
+    Node,
+    C,
 }
 
 struct PlatformInfo {
@@ -20,7 +24,7 @@ struct PlatformInfo {
     str: &'static str,
 }
 
-const PLATFORMS: [PlatformInfo; 5] = [
+const PLATFORMS: [PlatformInfo; 9] = [
     PlatformInfo {
         platform: Platform::Ios,
         str: "ios",
@@ -41,6 +45,22 @@ const PLATFORMS: [PlatformInfo; 5] = [
         platform: Platform::Web,
         str: "web",
     },
+    PlatformInfo {
+        platform: Platform::Python,
+        str: "python",
+    },
+    PlatformInfo {
+        platform: Platform::Jvm,
+        str: "jvm",
+    },
+    PlatformInfo {
+        platform: Platform::Node,
+        str: "node",
+    },
+    PlatformInfo {
+        platform: Platform::C,
+        str: "c",
+    },
 ];
 
 impl Platform {
@@ -74,6 +94,10 @@ impl Platform {
             Self::Flutter => "Flutter",
             Self::ReactNative => "ReactNative",
             Self::Web => "WASM",
+            Self::Python => "Python",
+            Self::Jvm => "JVM",
+            Self::Node => "Node",
+            Self::C => "C",
         }
     }
 
@@ -84,6 +108,10 @@ impl Platform {
             Self::Flutter => FLUTTER_BINDINGS_DIR,
             Self::ReactNative => REACT_NATIVE_BINDINGS_DIR,
             Self::Web => WEB_BINDINGS_DIR,
+            Self::Python => PYTHON_BINDINGS_DIR,
+            Self::Jvm => JVM_BINDINGS_DIR,
+            Self::Node => NODE_BINDINGS_DIR,
+            Self::C => C_BINDINGS_DIR,
         }
     }
 }
@@ -1,12 +1,19 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Error;
 use include_dir::include_dir;
 use include_dir::Dir;
+use mopro_ffi::app_config::android::AndroidBindingsNames;
+use walkdir::WalkDir;
 
 use super::Create;
 use crate::constants::Platform;
-use crate::create::utils::{check_bindings, copy_android_bindings, copy_embedded_dir, copy_keys};
+use crate::create::utils::{
+    check_bindings, copy_android_bindings, copy_embedded_dir, copy_keys, read_project_config,
+};
 use crate::print::print_footer_message;
 use crate::style::print_bold;
 use crate::style::print_green_bold;
@@ -18,6 +25,7 @@ impl Create for Android {
 
     fn create(project_dir: PathBuf) -> Result<(), Error> {
         let android_bindings_dir = check_bindings(&project_dir, Platform::Android)?;
+        let names = read_project_config(&project_dir)?.android_bindings_names();
 
         let target_dir = project_dir.join(Self::NAME);
         fs::create_dir_all(&target_dir)?;
@@ -27,10 +35,11 @@ impl Create for Android {
         let app_dir = target_dir.join("app");
         if let Some(bindings_dir) = android_bindings_dir {
             copy_android_bindings(&bindings_dir, &app_dir, "java")?;
+            rename_template_package(&app_dir, &names)?;
         } else {
             return Err(Error::msg(format!(
                 "{} are required to create the template. Please run 'mopro build' to generate them.",
-                Platform::Android.binding_dir()
+                names.bindings_dir
             )));
         }
 
@@ -54,3 +63,23 @@ impl Create for Android {
         print_footer_message();
     }
 }
+
+/// Import the configured Kotlin package in the template's sources.
+fn rename_template_package(app_dir: &Path, names: &AndroidBindingsNames) -> Result<(), Error> {
+    let default_import = format!(
+        "import uniffi.{}.",
+        AndroidBindingsNames::default().package_name
+    );
+    let import = format!("import uniffi.{}.", names.package_name);
+    if import == default_import {
+        return Ok(());
+    }
+    for entry in WalkDir::new(app_dir.join("src")) {
+        let entry = entry?;
+        if entry.path().extension().is_some_and(|ext| ext == "kt") {
+            let content = fs::read_to_string(entry.path())?;
+            fs::write(entry.path(), content.replace(&default_import, &import))?;
+        }
+    }
+    Ok(())
+}
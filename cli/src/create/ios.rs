@@ -1,11 +1,17 @@
 use anyhow::{Error, Result};
 use include_dir::include_dir;
 use include_dir::Dir;
-use std::{fs, path::PathBuf};
+use mopro_ffi::app_config::ios::IosBindingsNames;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use super::Create;
 use crate::constants::Platform;
-use crate::create::utils::{check_bindings, copy_embedded_dir, copy_ios_bindings, copy_keys};
+use crate::create::utils::{
+    check_bindings, copy_embedded_dir, copy_ios_bindings, copy_keys, read_project_config,
+};
 use crate::print::print_footer_message;
 use crate::style::print_bold;
 use crate::style::print_green_bold;
@@ -17,6 +23,7 @@ impl Create for Ios {
 
     fn create(project_dir: PathBuf) -> Result<()> {
         let ios_bindings_dir = check_bindings(&project_dir, Platform::Ios)?;
+        let names = read_project_config(&project_dir)?.ios_bindings_names();
 
         let target_dir = project_dir.join(Self::NAME);
         if target_dir.exists() {
@@ -31,11 +38,12 @@ impl Create for Ios {
         copy_embedded_dir(&IOS_TEMPLATE_DIR, &target_dir)?;
 
         if let Some(bindings_dir) = ios_bindings_dir {
-            copy_ios_bindings(bindings_dir, target_dir.clone())?;
+            copy_ios_bindings(bindings_dir, target_dir.clone(), &names.bindings_dir)?;
+            rename_template_bindings(&target_dir, &names)?;
         } else {
             return Err(Error::msg(format!(
                 "{} are required to create the template. Please run 'mopro build' to generate them.",
-                Platform::Ios.binding_dir()
+                names.bindings_dir
             )));
         }
         copy_keys(target_dir)?;
@@ -57,3 +65,17 @@ impl Create for Ios {
         print_footer_message();
     }
 }
+
+/// Point the template's Xcode project at the configured binding names.
+fn rename_template_bindings(target_dir: &Path, names: &IosBindingsNames) -> Result<()> {
+    let defaults = IosBindingsNames::default();
+    let pbxproj = target_dir
+        .join("MoproApp.xcodeproj")
+        .join("project.pbxproj");
+    let content = fs::read_to_string(&pbxproj)?
+        .replace(&defaults.bindings_dir, &names.bindings_dir)
+        .replace(&defaults.swift_file, &names.swift_file)
+        .replace(&defaults.xcframework, &names.xcframework);
+    fs::write(&pbxproj, content)?;
+    Ok(())
+}
@@ -18,7 +18,12 @@ use zip::ZipArchive;
 
 use mopro_ffi::app_config::constants::{ANDROID_JNILIBS_DIR, ANDROID_UNIFFI_DIR};
 
-use crate::{build::build_project, constants::Platform, style};
+use crate::{
+    build::{build_project, BuildOptions},
+    config::{read_config, Config},
+    constants::Platform,
+    style,
+};
 
 pub fn copy_android_bindings(
     android_bindings_dir: &Path,
@@ -45,8 +50,12 @@ pub fn copy_android_bindings(
     Ok(())
 }
 
-pub fn copy_ios_bindings(input_dir: PathBuf, output_dir: PathBuf) -> Result<()> {
-    let ios_bindings_target_dir = output_dir.join(Platform::Ios.binding_dir());
+pub fn copy_ios_bindings(
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    bindings_dir: &str,
+) -> Result<()> {
+    let ios_bindings_target_dir = output_dir.join(bindings_dir);
     if ios_bindings_target_dir.exists() {
         fs::remove_dir_all(&ios_bindings_target_dir)?;
     }
@@ -144,10 +153,20 @@ pub fn copy_keys(target_dir: std::path::PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// The `Config.toml` of the project, or the defaults if it has none.
+pub fn read_project_config(project_dir: &Path) -> Result<Config> {
+    let config_path = project_dir.join("Config.toml");
+    if config_path.exists() {
+        read_config(&config_path)
+    } else {
+        Ok(Config::default())
+    }
+}
+
 pub fn check_bindings(project_dir: &Path, platform: Platform) -> Result<Option<PathBuf>> {
-    let bindings_dir_name = platform.binding_dir();
+    let bindings_dir_name = read_project_config(project_dir)?.binding_dir(platform);
 
-    let bindings_dir = project_dir.join(bindings_dir_name);
+    let bindings_dir = project_dir.join(&bindings_dir_name);
     if bindings_dir.exists() && fs::read_dir(&bindings_dir)?.count() > 0 {
         return Ok(Some(bindings_dir));
     }
@@ -172,6 +191,7 @@ pub fn check_bindings(project_dir: &Path, platform: Platform) -> Result<Option<P
             &None,
             Some(false),
             false,
+            BuildOptions::default(),
         )?;
 
         if bindings_dir.exists() && fs::read_dir(&bindings_dir)?.count() > 0 {
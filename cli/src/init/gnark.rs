@@ -12,5 +12,15 @@ impl ProvingSystem for Gnark {
 
     const DEPENDENCIES: &'static str = r#"
 rust-gnark = "0.0.2"
+ark-bn254 = "0.5"
+ark-bls12-377 = "0.5"
+ark-bls12-381 = "0.5"
+ark-ec = "0.5"
+ark-ff = "0.5"
+hex = "0.4"
+    "#;
+
+    const DEV_DEPENDENCIES: &'static str = r#"
+ark-groth16 = "0.5"
     "#;
 }
@@ -12,11 +12,14 @@ impl ProvingSystem for Noir {
 
     const DEPENDENCIES: &'static str = r#"
 serde = { version = "1.0", features = ["derive"] }
-serde_json = "1.0.94"
 
 noir_rs = { package = "noir", git = "https://github.com/zkmopro/noir-rs", features = [
     "barretenberg",
 ], tag = "v1.0.0-beta.19" }
+acvm = { git = "https://github.com/noir-lang/noir", tag = "v1.0.0-beta.19" }
+bn254_blackbox_solver = { git = "https://github.com/noir-lang/noir", tag = "v1.0.0-beta.19" }
+nargo = { git = "https://github.com/noir-lang/noir", tag = "v1.0.0-beta.19" }
+noirc_artifacts = { git = "https://github.com/noir-lang/noir", tag = "v1.0.0-beta.19" }
     "#;
 
     const DEV_DEPENDENCIES: &'static str = r#"
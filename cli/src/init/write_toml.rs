@@ -19,6 +19,7 @@ wasm = ["mopro-ffi/wasm"]
 mopro-ffi = { version = "=0.3.6" }
 thiserror = "2.0.12"
 anyhow = "1.0.99"
+serde_json = "1.0.94"
 
 # CIRCOM_DEPENDENCIES
 # HALO2_DEPENDENCIES
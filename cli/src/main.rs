@@ -14,6 +14,7 @@ mod create;
 mod init;
 mod print;
 mod select;
+mod srs;
 mod style;
 mod update;
 
@@ -62,6 +63,26 @@ enum Commands {
         no_auto_update: bool,
         #[arg(long, help = "Show instruction message for build")]
         show: bool,
+        #[arg(
+            long,
+            help = "Print the commands the build would run without running them"
+        )]
+        dry_run: bool,
+        #[arg(long, requires = "dry_run", help = "Print the build plan as JSON")]
+        json: bool,
+        #[arg(long, help = "Rebuild bindings even if their inputs did not change")]
+        force: bool,
+        #[arg(
+            long,
+            help = "Number of architectures to build at once (default: `jobs` in Config.toml, or 1)"
+        )]
+        jobs: Option<usize>,
+        #[arg(
+            long,
+            default_value_t = 0,
+            help = "List the N largest symbols of each library in the size report"
+        )]
+        top_symbols: usize,
     },
     /// Create templates for the specified platform
     Create {
@@ -150,6 +171,32 @@ enum Commands {
         #[arg(long, help = "Show instruction message for build")]
         show: bool,
     },
+    /// Prepare per-circuit Noir SRS files from a local master SRS for offline proving
+    Srs {
+        #[arg(long, num_args = 1.., help = "Compiled Noir circuit(s) (.json) to prepare an SRS for.")]
+        circuit: Vec<String>,
+        #[arg(
+            long,
+            help = "Path to the master SRS: a .srs file or Aztec's raw g1.dat (requires --g2)."
+        )]
+        master_srs: Option<String>,
+        #[arg(long, help = "Path to g2.dat when --master-srs is a raw g1.dat file.")]
+        g2: Option<String>,
+        #[arg(long, help = "Expected SHA-256 of the master SRS file.")]
+        master_sha256: Option<String>,
+        #[arg(
+            long,
+            help = "Number of SRS points to keep (defaults to the circuit size reported by `bb gates`)."
+        )]
+        num_points: Option<u32>,
+        #[arg(
+            long,
+            help = "Directory for the generated .srs files (defaults to the circuit's directory)."
+        )]
+        output_dir: Option<String>,
+        #[arg(long, num_args = 1.., help = "Verify .srs file(s) against their .sha256 checksum.")]
+        verify: Vec<String>,
+    },
 }
 
 fn main() {
@@ -177,11 +224,21 @@ fn main() {
             auto_update,
             no_auto_update,
             show,
+            dry_run,
+            json,
+            force,
+            jobs,
+            top_symbols,
         } => {
             if *show {
                 print::print_build_success_message();
                 return;
             }
+            let dry_run = match (*dry_run, *json) {
+                (false, _) => None,
+                (true, false) => Some(build::PlanFormat::Human),
+                (true, true) => Some(build::PlanFormat::Json),
+            };
             let auto_update_flag = if *auto_update {
                 Some(true)
             } else if *no_auto_update {
@@ -189,7 +246,19 @@ fn main() {
             } else {
                 None
             };
-            match build::build_project(mode, platforms, architectures, auto_update_flag, false) {
+            match build::build_project(
+                mode,
+                platforms,
+                architectures,
+                auto_update_flag,
+                false,
+                build::BuildOptions {
+                    dry_run,
+                    force: *force,
+                    jobs: *jobs,
+                    top_symbols: *top_symbols,
+                },
+            ) {
                 Ok(_) => {}
                 Err(e) => style::print_red_bold(format!("Failed to build project: {e:?}")),
             }
@@ -273,7 +342,14 @@ fn main() {
             } else {
                 None
             };
-            match build::build_project(mode, platforms, architectures, auto_update_flag, false) {
+            match build::build_project(
+                mode,
+                platforms,
+                architectures,
+                auto_update_flag,
+                false,
+                build::BuildOptions::default(),
+            ) {
                 Ok(_) => {}
                 Err(e) => style::print_red_bold(format!("Failed to build project: {e:?}")),
             }
@@ -318,5 +394,26 @@ fn main() {
                 Err(e) => style::print_red_bold(format!("Failed to generate bindings: {e:?}")),
             }
         }
+
+        Commands::Srs {
+            circuit,
+            master_srs,
+            g2,
+            master_sha256,
+            num_points,
+            output_dir,
+            verify,
+        } => match srs::srs(
+            circuit,
+            master_srs,
+            g2,
+            master_sha256,
+            *num_points,
+            output_dir,
+            verify,
+        ) {
+            Ok(_) => {}
+            Err(e) => style::print_red_bold(format!("Failed to prepare SRS: {e:?}")),
+        },
     }
 }
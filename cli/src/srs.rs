@@ -0,0 +1,344 @@
+//! Offline SRS management for the Noir adapter.
+//!
+//! `generate_noir_proof` with `srs_path: None` lets barretenberg download the
+//! points it needs on first use, which breaks air-gapped builds. `mopro srs`
+//! slices a local master SRS (Aztec's `g1.dat` or an existing `.srs`) down to
+//! the size each circuit needs and writes per-circuit `.srs` files in the same
+//! layout noir-rs loads (see `test-vectors/noir/noir_multiplier2.srs`), each
+//! with a `.sha256` sidecar so the files can be verified before shipping.
+
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::style;
+
+/// Size of one uncompressed BN254 G1 point in `g1.dat`.
+const G1_POINT_SIZE: usize = 64;
+/// Size of the BN254 G2 point in `g2.dat`.
+const G2_SIZE: usize = 128;
+/// Extension of the checksum sidecar written next to each `.srs` file.
+const HASH_EXTENSION: &str = "sha256";
+
+/// SRS points in the bincode layout of noir-rs' `Srs`:
+/// `u64 g1 len | g1 | u64 g2 len | g2 | u32 num_points`, all little-endian.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Srs {
+    pub g1_data: Vec<u8>,
+    pub g2_data: Vec<u8>,
+    pub num_points: u32,
+}
+
+impl Srs {
+    /// Load a master SRS. `.srs` files are read as-is; anything else is read
+    /// as raw `g1.dat` points, which then requires `g2_path`.
+    pub fn load(path: &Path, g2_path: Option<&Path>) -> anyhow::Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        if path.extension().is_some_and(|ext| ext == "srs") {
+            return Self::from_bytes(&bytes)
+                .with_context(|| format!("Invalid SRS file {}", path.display()));
+        }
+
+        let Some(g2_path) = g2_path else {
+            anyhow::bail!(
+                "{} is not a .srs file; pass --g2 with the matching g2.dat",
+                path.display()
+            );
+        };
+        let g2_data =
+            fs::read(g2_path).with_context(|| format!("Failed to read {}", g2_path.display()))?;
+        if g2_data.len() != G2_SIZE {
+            anyhow::bail!(
+                "Expected {G2_SIZE} bytes of G2 data in {}, found {}",
+                g2_path.display(),
+                g2_data.len()
+            );
+        }
+        if bytes.len() % G1_POINT_SIZE != 0 {
+            anyhow::bail!(
+                "{} is not a whole number of {G1_POINT_SIZE}-byte G1 points",
+                path.display()
+            );
+        }
+        Ok(Self {
+            num_points: (bytes.len() / G1_POINT_SIZE) as u32,
+            g1_data: bytes,
+            g2_data,
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut rest = bytes;
+        let g1_data = take_vec(&mut rest)?;
+        let g2_data = take_vec(&mut rest)?;
+        let num_points = u32::from_le_bytes(
+            take(&mut rest, 4)?
+                .try_into()
+                .expect("take returns the requested length"),
+        );
+        if !rest.is_empty() {
+            anyhow::bail!("{} trailing bytes after SRS data", rest.len());
+        }
+        if g1_data.len() != num_points as usize * G1_POINT_SIZE {
+            anyhow::bail!(
+                "G1 data is {} bytes but the header declares {num_points} points",
+                g1_data.len()
+            );
+        }
+        Ok(Self {
+            g1_data,
+            g2_data,
+            num_points,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.g1_data.len() + self.g2_data.len() + 20);
+        out.extend_from_slice(&(self.g1_data.len() as u64).to_le_bytes());
+        out.extend_from_slice(&self.g1_data);
+        out.extend_from_slice(&(self.g2_data.len() as u64).to_le_bytes());
+        out.extend_from_slice(&self.g2_data);
+        out.extend_from_slice(&self.num_points.to_le_bytes());
+        out
+    }
+
+    /// Keep the first `num_points` G1 points. SRS points are a prefix of the
+    /// same powers of tau, so a smaller SRS is always a slice of a larger one.
+    pub fn slice(&self, num_points: u32) -> anyhow::Result<Self> {
+        if num_points > self.num_points {
+            anyhow::bail!(
+                "Circuit needs {num_points} points but the master SRS only has {}",
+                self.num_points
+            );
+        }
+        Ok(Self {
+            g1_data: self.g1_data[..num_points as usize * G1_POINT_SIZE].to_vec(),
+            g2_data: self.g2_data.clone(),
+            num_points,
+        })
+    }
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> anyhow::Result<&'a [u8]> {
+    if rest.len() < len {
+        anyhow::bail!("Unexpected end of SRS data");
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+fn take_vec(rest: &mut &[u8]) -> anyhow::Result<Vec<u8>> {
+    let len = u64::from_le_bytes(
+        take(rest, 8)?
+            .try_into()
+            .expect("take returns the requested length"),
+    );
+    Ok(take(rest, len as usize)?.to_vec())
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Number of SRS points barretenberg requests for a circuit of `circuit_size`
+/// gates: the next power of two plus one.
+pub fn points_for_circuit_size(circuit_size: u32) -> u32 {
+    circuit_size.next_power_of_two() + 1
+}
+
+/// Ask `bb gates` for the circuit size of a compiled Noir circuit.
+fn circuit_size(circuit: &Path) -> anyhow::Result<u32> {
+    let output = Command::new("bb")
+        .arg("gates")
+        .arg("-b")
+        .arg(circuit)
+        .output()
+        .context(
+            "Failed to run `bb gates`. Install barretenberg (bbup) or pass --num-points explicitly",
+        )?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`bb gates` failed for {}: {}",
+            circuit.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    parse_circuit_size(&String::from_utf8_lossy(&output.stdout))
+        .with_context(|| format!("Unexpected `bb gates` output for {}", circuit.display()))
+}
+
+fn parse_circuit_size(gates_json: &str) -> anyhow::Result<u32> {
+    let value: serde_json::Value = serde_json::from_str(gates_json)?;
+    value["functions"][0]["circuit_size"]
+        .as_u64()
+        .map(|size| size as u32)
+        .context("Missing `functions[0].circuit_size`")
+}
+
+/// `<circuit>.srs` for a compiled circuit, keeping dots in its name, e.g.
+/// `circuit.v2.srs` for `circuit.v2.json`.
+fn srs_file_name(circuit: &Path) -> anyhow::Result<String> {
+    let stem = circuit
+        .file_stem()
+        .with_context(|| format!("Invalid circuit path {}", circuit.display()))?;
+    Ok(format!("{}.srs", stem.to_string_lossy()))
+}
+
+fn hash_path(srs_path: &Path) -> PathBuf {
+    let mut name = srs_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(HASH_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Write the SRS and its checksum sidecar, returning the hex digest.
+fn write_srs(srs: &Srs, path: &Path) -> anyhow::Result<String> {
+    let bytes = srs.to_bytes();
+    let digest = sha256_hex(&bytes);
+    fs::write(path, &bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+    fs::write(hash_path(path), format!("{digest}\n"))
+        .with_context(|| format!("Failed to write checksum for {}", path.display()))?;
+    Ok(digest)
+}
+
+/// Check an `.srs` file against its `.sha256` sidecar and its own header.
+pub fn verify_srs_file(path: &Path) -> anyhow::Result<()> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let sidecar = hash_path(path);
+    let expected = fs::read_to_string(&sidecar)
+        .with_context(|| format!("Missing checksum file {}", sidecar.display()))?;
+    let actual = sha256_hex(&bytes);
+    if expected.trim() != actual {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, found {actual}",
+            path.display(),
+            expected.trim()
+        );
+    }
+    Srs::from_bytes(&bytes).with_context(|| format!("Invalid SRS file {}", path.display()))?;
+    Ok(())
+}
+
+pub fn srs(
+    circuits: &[String],
+    master: &Option<String>,
+    g2: &Option<String>,
+    master_sha256: &Option<String>,
+    num_points: Option<u32>,
+    output_dir: &Option<String>,
+    verify: &[String],
+) -> anyhow::Result<()> {
+    for path in verify {
+        verify_srs_file(Path::new(path))?;
+        style::print_green_bold(format!("✔ {path} matches its checksum"));
+    }
+    if circuits.is_empty() {
+        if verify.is_empty() {
+            anyhow::bail!(
+                "Nothing to do: pass --circuit to prepare SRS files or --verify to check them"
+            );
+        }
+        return Ok(());
+    }
+
+    let Some(master) = master else {
+        anyhow::bail!("--master-srs is required to prepare SRS files");
+    };
+    let master_path = Path::new(master);
+    if let Some(expected) = master_sha256 {
+        let bytes = fs::read(master_path)
+            .with_context(|| format!("Failed to read {}", master_path.display()))?;
+        let actual = sha256_hex(&bytes);
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!(
+                "Master SRS checksum mismatch: expected {}, found {actual}",
+                expected.trim()
+            );
+        }
+    }
+    let master_srs = Srs::load(master_path, g2.as_deref().map(Path::new))?;
+
+    for circuit in circuits {
+        let circuit_path = Path::new(circuit);
+        let points = match num_points {
+            Some(points) => points,
+            None => points_for_circuit_size(circuit_size(circuit_path)?),
+        };
+        let file_name = srs_file_name(circuit_path)?;
+        let out_dir = match output_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => circuit_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        };
+        fs::create_dir_all(&out_dir)
+            .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+        let out_path = out_dir.join(file_name);
+
+        let digest = write_srs(&master_srs.slice(points)?, &out_path)?;
+        style::print_green_bold(format!(
+            "✔ Wrote {} ({points} points, sha256 {digest})",
+            out_path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_srs(num_points: u32) -> Srs {
+        Srs {
+            g1_data: (0..num_points as usize * G1_POINT_SIZE)
+                .map(|i| i as u8)
+                .collect(),
+            g2_data: vec![7; G2_SIZE],
+            num_points,
+        }
+    }
+
+    #[test]
+    fn reads_the_noir_test_vector_srs() {
+        let bytes = fs::read("src/template/init/test-vectors/noir/noir_multiplier2.srs").unwrap();
+        let srs = Srs::from_bytes(&bytes).unwrap();
+        assert_eq!(srs.num_points, 513);
+        assert_eq!(srs.g2_data.len(), G2_SIZE);
+        assert_eq!(srs.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn slices_a_prefix_of_the_master_srs() {
+        let master = test_srs(17);
+        let sliced = master.slice(9).unwrap();
+        assert_eq!(sliced.num_points, 9);
+        assert_eq!(sliced.g1_data, master.g1_data[..9 * G1_POINT_SIZE]);
+        assert!(master.slice(18).is_err());
+    }
+
+    #[test]
+    fn names_the_srs_after_the_circuit() {
+        assert_eq!(
+            srs_file_name(Path::new("out/circuit.v2.json")).unwrap(),
+            "circuit.v2.srs"
+        );
+        assert_eq!(
+            srs_file_name(Path::new("multiplier2.json")).unwrap(),
+            "multiplier2.srs"
+        );
+    }
+
+    #[test]
+    fn points_cover_the_next_power_of_two() {
+        assert_eq!(points_for_circuit_size(512), 513);
+        assert_eq!(points_for_circuit_size(300), 513);
+        assert_eq!(
+            parse_circuit_size(r#"{"functions":[{"acir_opcodes":1,"circuit_size":300}]}"#).unwrap(),
+            300
+        );
+    }
+}
@@ -30,6 +30,7 @@ import androidx.compose.ui.unit.dp
 import androidx.compose.ui.unit.sp
 import uniffi.mopro.generateGnarkProof
 import uniffi.mopro.verifyGnarkProof
+import uniffi.mopro.GnarkCurve
 import uniffi.mopro.GnarkProofResult
 
 @Composable
@@ -99,7 +100,7 @@ fun GnarkComponent() {
                         Thread {
                             try {
                                 val startTime = System.currentTimeMillis()
-                                proofResult = generateGnarkProof(r1csPath, pkPath, witnessJson)
+                                proofResult = generateGnarkProof(r1csPath, pkPath, witnessJson, GnarkCurve.BN254)
                                 val endTime = System.currentTimeMillis()
                                 provingTime = "${endTime - startTime} ms"
                             } catch (e: Exception) {
@@ -1,17 +1,25 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod gnark;
 #[cfg(not(target_arch = "wasm32"))]
-pub use gnark::{generate_gnark_proof, verify_gnark_proof, GnarkProofResult};
+pub use gnark::{
+    convert_gnark_proof, convert_gnark_verifying_key, generate_gnark_proof, verify_gnark_proof,
+    GnarkCurve, GnarkProofResult, GnarkVerifyingKey, GnarkWitnessBuilder,
+};
 
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod gnark_tests {
-    use crate::gnark::{generate_gnark_proof, verify_gnark_proof};
+    use crate::gnark::{
+        convert_gnark_proof, convert_gnark_verifying_key, generate_gnark_proof, verify_gnark_proof,
+        GnarkCurve, GnarkWitnessBuilder,
+    };
+    use crate::{G1, G2};
+    use ark_bn254::{Bn254, Fq2, Fr, G1Affine, G2Affine};
+    use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
 
     const R1CS_PATH: &str = "./test-vectors/gnark/cubic_circuit.r1cs";
     const PK_PATH: &str = "./test-vectors/gnark/cubic_circuit.pk";
     const VK_PATH: &str = "./test-vectors/gnark/cubic_circuit.vk";
-
     #[test]
     fn test_gnark_cubic_circuit() {
         // x=3: x^3 + x + 5 = 27 + 3 + 5 = 35
@@ -21,6 +29,7 @@ mod gnark_tests {
             R1CS_PATH.to_string(),
             PK_PATH.to_string(),
             witness_json,
+            GnarkCurve::Bn254,
         );
         assert!(result.is_ok(), "Proof generation should succeed");
 
@@ -39,4 +48,128 @@ mod gnark_tests {
         assert!(valid.is_ok(), "Verification should not error");
         assert!(valid.unwrap(), "Proof should be valid");
     }
+
+    #[test]
+    fn test_gnark_rejects_curve_mismatch() {
+        let witness_json = r#"{"X": "3", "Y": "35"}"#.to_string();
+
+        let result = generate_gnark_proof(
+            R1CS_PATH.to_string(),
+            PK_PATH.to_string(),
+            witness_json.clone(),
+            GnarkCurve::Bls12_381,
+        );
+        assert!(result.is_err(), "BN254 circuit should reject BLS12-381");
+
+        let mut proof_result = generate_gnark_proof(
+            R1CS_PATH.to_string(),
+            PK_PATH.to_string(),
+            witness_json,
+            GnarkCurve::Bn254,
+        )
+        .unwrap();
+        proof_result.curve = GnarkCurve::Bls12_377;
+        let valid = verify_gnark_proof(R1CS_PATH.to_string(), VK_PATH.to_string(), proof_result);
+        assert!(
+            valid.is_err(),
+            "Proof with a mismatched curve should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_convert_gnark_proof_and_vk() {
+        let witness_json = r#"{"X": "3", "Y": "35"}"#.to_string();
+        let proof_result = generate_gnark_proof(
+            R1CS_PATH.to_string(),
+            PK_PATH.to_string(),
+            witness_json,
+            GnarkCurve::Bn254,
+        )
+        .unwrap();
+
+        let circom_proof = convert_gnark_proof(proof_result).unwrap();
+        assert_eq!(circom_proof.inputs, vec!["35".to_string()]);
+        assert_eq!(circom_proof.proof.a.z, "1");
+        assert_eq!(circom_proof.proof.b.x.len(), 2);
+
+        let vk = convert_gnark_verifying_key(VK_PATH.to_string()).unwrap();
+        // One point per public input plus the constant term
+        assert_eq!(vk.ic.len(), 2);
+        assert_eq!(
+            vk.alpha.x,
+            "7981542505514799803994411514312683693618866651416084679393606630113075710003"
+        );
+    }
+
+    fn ark_g1(point: &G1) -> G1Affine {
+        G1Affine::new(point.x.parse().unwrap(), point.y.parse().unwrap())
+    }
+
+    fn ark_g2(point: &G2) -> G2Affine {
+        G2Affine::new(
+            Fq2::new(point.x[0].parse().unwrap(), point.x[1].parse().unwrap()),
+            Fq2::new(point.y[0].parse().unwrap(), point.y[1].parse().unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_converted_gnark_proof_verifies_with_arkworks() {
+        let witness_json = r#"{"X": "3", "Y": "35"}"#.to_string();
+        let proof_result = generate_gnark_proof(
+            R1CS_PATH.to_string(),
+            PK_PATH.to_string(),
+            witness_json,
+            GnarkCurve::Bn254,
+        )
+        .unwrap();
+        let circom_proof = convert_gnark_proof(proof_result).unwrap();
+        let vk = convert_gnark_verifying_key(VK_PATH.to_string()).unwrap();
+
+        let pvk = prepare_verifying_key(&VerifyingKey::<Bn254> {
+            alpha_g1: ark_g1(&vk.alpha),
+            beta_g2: ark_g2(&vk.beta),
+            gamma_g2: ark_g2(&vk.gamma),
+            delta_g2: ark_g2(&vk.delta),
+            gamma_abc_g1: vk.ic.iter().map(ark_g1).collect(),
+        });
+        let proof = Proof::<Bn254> {
+            a: ark_g1(&circom_proof.proof.a),
+            b: ark_g2(&circom_proof.proof.b),
+            c: ark_g1(&circom_proof.proof.c),
+        };
+        let inputs: Vec<Fr> = circom_proof
+            .inputs
+            .iter()
+            .map(|input| input.parse().unwrap())
+            .collect();
+        assert!(Groth16::<Bn254>::verify_proof(&pvk, &proof, &inputs).unwrap());
+
+        let wrong_inputs = vec![Fr::from(36u64)];
+        assert!(!Groth16::<Bn254>::verify_proof(&pvk, &proof, &wrong_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_gnark_witness_builder() {
+        let builder = GnarkWitnessBuilder::new(R1CS_PATH.to_string()).unwrap();
+        assert_eq!(builder.public_names(), vec!["Y".to_string()]);
+        assert_eq!(builder.secret_names(), vec!["X".to_string()]);
+
+        assert!(builder.set("Z".to_string(), "3".to_string()).is_err());
+        assert!(builder.set("X".to_string(), "three".to_string()).is_err());
+        assert!(builder.to_json().is_err(), "Missing fields should be rejected");
+
+        builder.set("X".to_string(), "3".to_string()).unwrap();
+        builder.set_json(r#"{"Y": 35}"#.to_string()).unwrap();
+        assert!(builder.missing_names().is_empty());
+
+        let proof_result = generate_gnark_proof(
+            R1CS_PATH.to_string(),
+            PK_PATH.to_string(),
+            builder.to_json().unwrap(),
+            GnarkCurve::Bn254,
+        )
+        .unwrap();
+        let valid = verify_gnark_proof(R1CS_PATH.to_string(), VK_PATH.to_string(), proof_result);
+        assert!(valid.unwrap());
+    }
 }
@@ -1,5 +1,5 @@
-fn main() {
+fn main() -> anyhow::Result<()> {
     // A simple wrapper around a build command provided by mopro.
     // In the future this will likely be published in the mopro crate itself.
-    mopro_ffi::app_config::ios::build();
+    mopro_ffi::app_config::ios::build()
 }
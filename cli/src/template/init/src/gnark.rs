@@ -1,41 +1,88 @@
-use crate::MoproError;
-use std::sync::Once;
+use crate::{CircomProof, CircomProofResult, MoproError, G1, G2};
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, Once};
 
 /// Guards one-time initialization of the gnark Go runtime.
 static GNARK_INIT: Once = Once::new();
 
-/// Result of a gnark Groth16 BN254 proof generation.
+/// Elliptic curves of gnark circuits, keys and proofs.
 ///
-/// Both fields are hex-encoded gnark binary serializations:
+/// The curve is validated against the circuit, keys and proofs. rust-gnark
+/// only proves and verifies on BN254, so the BLS12 curves are detected and
+/// rejected with a clear error instead of failing inside gnark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum GnarkCurve {
+    Bn254,
+    Bls12_381,
+    Bls12_377,
+}
+
+impl GnarkCurve {
+    /// gnark's `ecc.ID` name for the curve.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GnarkCurve::Bn254 => "bn254",
+            GnarkCurve::Bls12_381 => "bls12_381",
+            GnarkCurve::Bls12_377 => "bls12_377",
+        }
+    }
+
+    /// Scalar field modulus, as gnark writes it in the constraint system header.
+    fn scalar_field(&self) -> &'static str {
+        match self {
+            GnarkCurve::Bn254 => "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+            GnarkCurve::Bls12_381 => {
+                "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+            }
+            GnarkCurve::Bls12_377 => {
+                "12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000001"
+            }
+        }
+    }
+}
+
+/// Result of a gnark Groth16 proof generation.
+///
+/// `proof` and `public_inputs` are hex-encoded gnark binary serializations:
 /// - `proof`: compressed Groth16 proof
 /// - `public_inputs`: public witness
+///
+/// `curve` is the curve the proof was generated on.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct GnarkProofResult {
     pub proof: String,
     pub public_inputs: String,
+    pub curve: GnarkCurve,
 }
 
-/// Generate a Groth16 BN254 proof using gnark.
+/// Generate a Groth16 proof using gnark.
 ///
 /// # Arguments
 ///
 /// * `r1cs_path` - Path to the `.r1cs` file (CBOR binary)
 /// * `pk_path` - Path to the `.pk` file (gnark binary)
 /// * `witness_json` - JSON object mapping circuit field names to decimal string values
+/// * `curve` - Curve the circuit was compiled for
 ///
 /// # Errors
 ///
-/// Returns [`MoproError::GnarkError`] if proof generation fails.
+/// Returns [`MoproError::GnarkError`] if the circuit was compiled for another
+/// curve, the curve is not BN254 or proof generation fails.
 #[cfg_attr(feature = "uniffi", uniffi::export)]
 pub fn generate_gnark_proof(
     r1cs_path: String,
     pk_path: String,
     witness_json: String,
+    curve: GnarkCurve,
 ) -> Result<GnarkProofResult, MoproError> {
-    GNARK_INIT.call_once(|| {
-        rust_gnark::init().expect("Failed to initialize gnark runtime");
-    });
+    init_gnark();
+    ensure_circuit_curve(&r1cs_path, curve)?;
+    ensure_bn254(curve)?;
 
     let result = rust_gnark::groth16_prove(&r1cs_path, &pk_path, &witness_json)
         .map_err(|e| MoproError::GnarkError(e.to_string()))?;
@@ -43,10 +90,11 @@ pub fn generate_gnark_proof(
     Ok(GnarkProofResult {
         proof: result.proof,
         public_inputs: result.public_inputs,
+        curve,
     })
 }
 
-/// Verify a Groth16 BN254 proof using gnark.
+/// Verify a Groth16 proof using gnark.
 ///
 /// # Arguments
 ///
@@ -60,16 +108,18 @@ pub fn generate_gnark_proof(
 ///
 /// # Errors
 ///
-/// Returns [`MoproError::GnarkError`] if verification encounters an error.
+/// Returns [`MoproError::GnarkError`] if the proof and the verifying key are
+/// not both on the proof's curve, the curve is not BN254, or if verification
+/// encounters an error.
 #[cfg_attr(feature = "uniffi", uniffi::export)]
 pub fn verify_gnark_proof(
     r1cs_path: String,
     vk_path: String,
     proof_result: GnarkProofResult,
 ) -> Result<bool, MoproError> {
-    GNARK_INIT.call_once(|| {
-        rust_gnark::init().expect("Failed to initialize gnark runtime");
-    });
+    init_gnark();
+    ensure_proof_curve(&vk_path, &proof_result)?;
+    ensure_bn254(proof_result.curve)?;
 
     let inner = rust_gnark::Groth16ProofResult {
         proof: proof_result.proof,
@@ -79,3 +129,722 @@ pub fn verify_gnark_proof(
     rust_gnark::groth16_verify(&r1cs_path, &vk_path, &inner)
         .map_err(|e| MoproError::GnarkError(e.to_string()))
 }
+
+fn init_gnark() {
+    GNARK_INIT.call_once(|| {
+        rust_gnark::init().expect("Failed to initialize gnark runtime");
+    });
+}
+
+/// Reject a circuit compiled for a different curve than `expected`.
+fn ensure_circuit_curve(ccs_path: &str, expected: GnarkCurve) -> Result<(), MoproError> {
+    let ccs = std::fs::read(ccs_path)
+        .map_err(|e| MoproError::GnarkError(format!("Failed to read {}: {}", ccs_path, e)))?;
+    let actual = circuit_curve(&ccs).ok_or_else(|| {
+        MoproError::GnarkError(format!("Unable to detect the curve of {}", ccs_path))
+    })?;
+    if actual != expected {
+        return Err(MoproError::GnarkError(format!(
+            "Curve mismatch: {} was compiled for {}, got {}",
+            ccs_path,
+            actual.as_str(),
+            expected.as_str()
+        )));
+    }
+    Ok(())
+}
+
+/// Reject a proof whose points, or those of its verifying key, are not on the
+/// proof's curve.
+///
+/// gnark does not tag keys and proofs with their curve, so it is read from
+/// their first point, which is a G1 point for both.
+fn ensure_proof_curve(vk_path: &str, proof_result: &GnarkProofResult) -> Result<(), MoproError> {
+    let vk = std::fs::read(vk_path)
+        .map_err(|e| MoproError::GnarkError(format!("Failed to read {}: {}", vk_path, e)))?;
+    let proof = decode_hex(&proof_result.proof)?;
+    for (name, data) in [(vk_path, &vk[..]), ("proof", &proof[..])] {
+        let actual = point_curve(data).ok_or_else(|| {
+            MoproError::GnarkError(format!("Unable to detect the curve of {}", name))
+        })?;
+        if actual != proof_result.curve {
+            return Err(MoproError::GnarkError(format!(
+                "Curve mismatch: {} is on {}, got {}",
+                name,
+                actual.as_str(),
+                proof_result.curve.as_str()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Detect the curve of a gnark-encoded G1 point.
+///
+/// The BLS12 curves are tried first: 48 bytes almost never decode to a point
+/// of their prime-order subgroup by chance, while half of all 32-byte values
+/// are valid BN254 x-coordinates.
+fn point_curve(data: &[u8]) -> Option<GnarkCurve> {
+    if is_g1_point::<ark_bls12_381::g1::Config>(data) {
+        Some(GnarkCurve::Bls12_381)
+    } else if is_g1_point::<ark_bls12_377::g1::Config>(data) {
+        Some(GnarkCurve::Bls12_377)
+    } else if is_g1_point::<ark_bn254::g1::Config>(data) {
+        Some(GnarkCurve::Bn254)
+    } else {
+        None
+    }
+}
+
+fn is_g1_point<P>(data: &[u8]) -> bool
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+{
+    decode_g1::<P>(data).is_some_and(|point| {
+        point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+    })
+}
+
+/// Decode a compressed or raw G1 point, without checking it is on the curve.
+fn decode_g1<P>(data: &[u8]) -> Option<Affine<P>>
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+{
+    let width = (P::BaseField::MODULUS_BIT_SIZE as usize).div_ceil(8);
+    let coordinate = |index: usize| -> Option<P::BaseField> {
+        let mut bytes = data.get(index * width..(index + 1) * width)?.to_vec();
+        if index == 0 {
+            bytes[0] &= !FLAG_MASK;
+        }
+        let value = P::BaseField::from_be_bytes_mod_order(&bytes);
+        // Reject encodings that are not reduced modulo the field
+        value
+            .into_bigint()
+            .to_bytes_be()
+            .ends_with(&bytes)
+            .then_some(value)
+    };
+    match *data.first()? & FLAG_MASK {
+        FLAG_INFINITY => None,
+        FLAG_UNCOMPRESSED => Some(Affine::new_unchecked(coordinate(0)?, coordinate(1)?)),
+        flag => Affine::get_point_from_x_unchecked(coordinate(0)?, flag == FLAG_LARGEST),
+    }
+}
+
+/// Detect the curve of a gnark constraint system from the `ScalarField`
+/// entry of its CBOR header.
+fn circuit_curve(ccs: &[u8]) -> Option<GnarkCurve> {
+    let modulus = ccs_header(ccs)?.scalar_field?;
+    [
+        GnarkCurve::Bn254,
+        GnarkCurve::Bls12_381,
+        GnarkCurve::Bls12_377,
+    ]
+    .into_iter()
+    .find(|curve| curve.scalar_field() == modulus)
+}
+
+/// Read the `Public` and `Secret` variable names from a gnark constraint
+/// system, dropping the constant `1` wire.
+fn circuit_schema(ccs: &[u8]) -> Option<(Vec<String>, Vec<String>)> {
+    let header = ccs_header(ccs)?;
+    let public = header
+        .public?
+        .into_iter()
+        .filter(|name| name != "1")
+        .collect();
+    Some((public, header.secret?))
+}
+
+/// Entries of the CBOR header of a gnark constraint system used here.
+#[derive(Default)]
+struct CcsHeader {
+    scalar_field: Option<String>,
+    public: Option<Vec<String>>,
+    secret: Option<Vec<String>>,
+}
+
+/// Decode the CBOR header of a gnark constraint system.
+///
+/// The file starts with eight little-endian `u64`s. The CBOR section comes
+/// after three binary sections whose byte lengths are words 4 to 6, and its
+/// own length is word 7.
+fn ccs_header(ccs: &[u8]) -> Option<CcsHeader> {
+    let word = |i: usize| -> Option<usize> {
+        let bytes = ccs.get(i * 8..(i + 1) * 8)?.try_into().ok()?;
+        usize::try_from(u64::from_le_bytes(bytes)).ok()
+    };
+    let start = [4, 5, 6]
+        .into_iter()
+        .try_fold(64usize, |offset, i| offset.checked_add(word(i)?))?;
+    let end = start.checked_add(word(7)?)?;
+    let mut cbor = Cbor::new(ccs.get(start..end)?);
+
+    let mut header = CcsHeader::default();
+    for _ in 0..cbor.length(5)? {
+        match cbor.text()? {
+            "ScalarField" => header.scalar_field = Some(cbor.text()?.to_string()),
+            "Public" => header.public = Some(cbor.text_array()?),
+            "Secret" => header.secret = Some(cbor.text_array()?),
+            _ => cbor.skip()?,
+        }
+    }
+    cbor.is_empty().then_some(header)
+}
+
+/// Minimal reader for definite-length CBOR items.
+struct Cbor<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Cbor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.data.len() {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    /// Read an item header, returning its major type and argument.
+    fn header(&mut self) -> Option<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let argument = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            n @ 24..=27 => self
+                .take(1 << (n - 24))?
+                .iter()
+                .fold(0, |acc, b| (acc << 8) | *b as u64),
+            // Indefinite lengths and reserved values are not used by gnark
+            _ => return None,
+        };
+        Some((initial >> 5, argument))
+    }
+
+    /// Read the header of an item of major type `major` and return its length.
+    fn length(&mut self, major: u8) -> Option<usize> {
+        match self.header()? {
+            (m, len) if m == major => usize::try_from(len).ok(),
+            _ => None,
+        }
+    }
+
+    fn text(&mut self) -> Option<&'a str> {
+        let len = self.length(3)?;
+        std::str::from_utf8(self.take(len)?).ok()
+    }
+
+    fn text_array(&mut self) -> Option<Vec<String>> {
+        (0..self.length(4)?)
+            .map(|_| self.text().map(str::to_string))
+            .collect()
+    }
+
+    /// Skip one item, including any nested items.
+    fn skip(&mut self) -> Option<()> {
+        let (major, argument) = self.header()?;
+        let count = usize::try_from(argument).ok()?;
+        match major {
+            2 | 3 => {
+                self.take(count)?;
+            }
+            4 => (0..count).try_for_each(|_| self.skip())?,
+            5 => (0..count.checked_mul(2)?).try_for_each(|_| self.skip())?,
+            6 => self.skip()?,
+            _ => {}
+        }
+        Some(())
+    }
+}
+
+/// Typed builder for gnark witnesses.
+///
+/// Reads the public and secret variable names from the circuit's `.r1cs` so
+/// that misnamed or missing fields are reported before reaching the gnark
+/// runtime. Names are gnark's flattened leaf names: nested struct fields are
+/// joined with `_` (`P_X`) and array elements are indexed (`Arr_0`). As a
+/// field name may itself contain `_`, names are kept flat rather than split.
+///
+/// Use [`GnarkWitnessBuilder::to_json`] to get the `witness_json` expected by
+/// [`generate_gnark_proof`].
+#[derive(Debug)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct GnarkWitnessBuilder {
+    public: Vec<String>,
+    secret: Vec<String>,
+    values: Mutex<BTreeMap<String, String>>,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl GnarkWitnessBuilder {
+    /// Create a builder for the circuit at `r1cs_path`.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(r1cs_path: String) -> Result<Arc<Self>, MoproError> {
+        let ccs = std::fs::read(&r1cs_path)
+            .map_err(|e| MoproError::GnarkError(format!("Failed to read {}: {}", r1cs_path, e)))?;
+        let (public, secret) = circuit_schema(&ccs).ok_or_else(|| {
+            MoproError::GnarkError(format!(
+                "Unable to read the witness schema of {}",
+                r1cs_path
+            ))
+        })?;
+        Ok(Arc::new(Self {
+            public,
+            secret,
+            values: Mutex::new(BTreeMap::new()),
+        }))
+    }
+
+    pub fn public_names(&self) -> Vec<String> {
+        self.public.clone()
+    }
+
+    pub fn secret_names(&self) -> Vec<String> {
+        self.secret.clone()
+    }
+
+    /// Set a field to a decimal or `0x`-prefixed hex value.
+    pub fn set(&self, name: String, value: String) -> Result<(), MoproError> {
+        if !self.public.contains(&name) && !self.secret.contains(&name) {
+            return Err(MoproError::GnarkError(format!(
+                "Unknown witness field `{}`, expected one of: {}",
+                name,
+                self.names().join(", ")
+            )));
+        }
+        if !is_field_value(&value) {
+            return Err(MoproError::GnarkError(format!(
+                "Invalid value `{}` for witness field `{}`",
+                value, name
+            )));
+        }
+        self.values.lock().unwrap().insert(name, value);
+        Ok(())
+    }
+
+    /// Set every field found in a witness JSON object. Nested objects and
+    /// arrays are flattened to gnark's leaf names.
+    pub fn set_json(&self, witness_json: String) -> Result<(), MoproError> {
+        let json: serde_json::Value = serde_json::from_str(&witness_json)
+            .map_err(|e| MoproError::GnarkError(format!("Invalid witness JSON: {}", e)))?;
+        let mut leaves = Vec::new();
+        flatten_json(&json, String::new(), &mut leaves)?;
+        for (name, value) in leaves {
+            self.set(name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Fields that have not been set yet.
+    pub fn missing_names(&self) -> Vec<String> {
+        let values = self.values.lock().unwrap();
+        self.names()
+            .into_iter()
+            .filter(|name| !values.contains_key(name))
+            .collect()
+    }
+
+    /// Serialize the witness as a flat JSON object keyed by leaf name, as
+    /// gnark expects.
+    pub fn to_json(&self) -> Result<String, MoproError> {
+        let missing = self.missing_names();
+        if !missing.is_empty() {
+            return Err(MoproError::GnarkError(format!(
+                "Missing witness fields: {}",
+                missing.join(", ")
+            )));
+        }
+        let values = self.values.lock().unwrap();
+        let json: serde_json::Map<String, serde_json::Value> = values
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone().into()))
+            .collect();
+        Ok(serde_json::Value::Object(json).to_string())
+    }
+}
+
+impl GnarkWitnessBuilder {
+    fn names(&self) -> Vec<String> {
+        self.public.iter().chain(&self.secret).cloned().collect()
+    }
+}
+
+fn is_field_value(value: &str) -> bool {
+    match value.strip_prefix("0x") {
+        Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()),
+    }
+}
+
+fn flatten_json(
+    json: &serde_json::Value,
+    prefix: String,
+    leaves: &mut Vec<(String, String)>,
+) -> Result<(), MoproError> {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}_{}", prefix, key)
+        }
+    };
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                flatten_json(value, join(key), leaves)?;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten_json(value, join(&i.to_string()), leaves)?;
+            }
+        }
+        serde_json::Value::String(value) => leaves.push((prefix, value.clone())),
+        serde_json::Value::Number(value) => leaves.push((prefix, value.to_string())),
+        _ => {
+            return Err(MoproError::GnarkError(format!(
+                "Unsupported value for witness field `{}`",
+                prefix
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Groth16 verifying key in the affine decimal form used by [`CircomProof`].
+///
+/// Mirrors snarkjs' `verification_key.json`: `ic` holds one point per public
+/// input plus the constant term.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct GnarkVerifyingKey {
+    pub alpha: G1,
+    pub beta: G2,
+    pub gamma: G2,
+    pub delta: G2,
+    pub ic: Vec<G1>,
+    pub curve: String,
+}
+
+/// Convert a gnark Groth16 proof into the [`CircomProofResult`] representation.
+///
+/// The proof can then be verified by the arkworks verifier of the circom
+/// adapter or passed to snarkjs-style Solidity verifiers. Public inputs are
+/// returned as decimal strings. Only BN254 proofs without commitments are
+/// supported.
+///
+/// # Errors
+///
+/// Returns [`MoproError::GnarkError`] if the proof cannot be decoded.
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn convert_gnark_proof(
+    proof_result: GnarkProofResult,
+) -> Result<CircomProofResult, MoproError> {
+    ensure_bn254(proof_result.curve)?;
+    let proof = decode_hex(&proof_result.proof)?;
+    let public_inputs = decode_hex(&proof_result.public_inputs)?;
+
+    let mut reader = GnarkReader::new(&proof);
+    let a = reader.g1()?;
+    let b = reader.g2()?;
+    let c = reader.g1()?;
+    if reader.remaining() > 0 && reader.u32()? != 0 {
+        return Err(MoproError::GnarkError(
+            "Proofs with commitments have no Circom representation".to_string(),
+        ));
+    }
+
+    Ok(CircomProofResult {
+        proof: CircomProof {
+            a,
+            b,
+            c,
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+        },
+        inputs: decode_public_witness(&public_inputs)?,
+    })
+}
+
+/// Convert a gnark Groth16 verifying key file into [`GnarkVerifyingKey`].
+///
+/// # Arguments
+///
+/// * `vk_path` - Path to the `.vk` file (gnark binary, compressed or raw)
+///
+/// # Errors
+///
+/// Returns [`MoproError::GnarkError`] if the key cannot be read or decoded.
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn convert_gnark_verifying_key(vk_path: String) -> Result<GnarkVerifyingKey, MoproError> {
+    let vk = std::fs::read(&vk_path)
+        .map_err(|e| MoproError::GnarkError(format!("Failed to read {}: {}", vk_path, e)))?;
+
+    let mut reader = GnarkReader::new(&vk);
+    let alpha = reader.g1()?;
+    let _beta_g1 = reader.g1()?;
+    let beta = reader.g2()?;
+    let gamma = reader.g2()?;
+    let _delta_g1 = reader.g1()?;
+    let delta = reader.g2()?;
+    let ic = (0..reader.u32()?)
+        .map(|_| reader.g1())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(GnarkVerifyingKey {
+        alpha,
+        beta,
+        gamma,
+        delta,
+        ic,
+        curve: "bn128".to_string(),
+    })
+}
+
+/// Reject curves rust-gnark cannot prove, verify or convert on.
+fn ensure_bn254(curve: GnarkCurve) -> Result<(), MoproError> {
+    if curve != GnarkCurve::Bn254 {
+        return Err(MoproError::GnarkError(format!(
+            "Only bn254 is supported, got {}",
+            curve.as_str()
+        )));
+    }
+    Ok(())
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, MoproError> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| MoproError::GnarkError(format!("Invalid hex: {}", e)))
+}
+
+/// Decode a gnark public witness (`nbPublic | nbSecret | len | elements`).
+fn decode_public_witness(witness: &[u8]) -> Result<Vec<String>, MoproError> {
+    let mut reader = GnarkReader::new(witness);
+    let _nb_public = reader.u32()?;
+    let _nb_secret = reader.u32()?;
+    (0..reader.u32()?)
+        .map(|_| {
+            reader
+                .take(32)
+                .map(|bytes| to_decimal(Fr::from_be_bytes_mod_order(bytes)))
+        })
+        .collect()
+}
+
+/// Top two bits of the first byte of a gnark-encoded point.
+const FLAG_MASK: u8 = 0b11 << 6;
+const FLAG_UNCOMPRESSED: u8 = 0b00 << 6;
+const FLAG_INFINITY: u8 = 0b01 << 6;
+const FLAG_LARGEST: u8 = 0b11 << 6;
+
+/// Sequential reader over gnark's big-endian BN254 encoding, accepting both
+/// compressed and raw (uncompressed) points.
+struct GnarkReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> GnarkReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MoproError> {
+        if self.bytes.len() < len {
+            return Err(MoproError::GnarkError(
+                "Unexpected end of gnark data".to_string(),
+            ));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, MoproError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a field element, clearing the flag bits when it heads a point.
+    fn fq(&mut self, clear_flags: bool) -> Result<Fq, MoproError> {
+        let mut bytes = self.take(32)?.to_vec();
+        if clear_flags {
+            bytes[0] &= !FLAG_MASK;
+        }
+        Ok(Fq::from_be_bytes_mod_order(&bytes))
+    }
+
+    fn peek_flag(&self) -> Result<u8, MoproError> {
+        self.bytes
+            .first()
+            .map(|b| b & FLAG_MASK)
+            .ok_or_else(|| MoproError::GnarkError("Unexpected end of gnark data".to_string()))
+    }
+
+    fn g1(&mut self) -> Result<G1, MoproError> {
+        let flag = self.peek_flag()?;
+        let x = self.fq(true)?;
+        let point = match flag {
+            FLAG_INFINITY => return Ok(g1_infinity()),
+            FLAG_UNCOMPRESSED => G1Affine::new_unchecked(x, self.fq(false)?),
+            _ => G1Affine::get_point_from_x_unchecked(x, flag == FLAG_LARGEST)
+                .ok_or_else(|| MoproError::GnarkError("Invalid G1 point".to_string()))?,
+        };
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(MoproError::GnarkError("Invalid G1 point".to_string()));
+        }
+        Ok(G1 {
+            x: to_decimal(point.x),
+            y: to_decimal(point.y),
+            z: "1".to_string(),
+        })
+    }
+
+    fn g2(&mut self) -> Result<G2, MoproError> {
+        let flag = self.peek_flag()?;
+        // gnark writes E2 elements as A1 || A0.
+        let x_a1 = self.fq(true)?;
+        let x_a0 = self.fq(false)?;
+        let x = Fq2::new(x_a0, x_a1);
+        let point = match flag {
+            FLAG_INFINITY => return Ok(g2_infinity()),
+            FLAG_UNCOMPRESSED => {
+                let y_a1 = self.fq(false)?;
+                let y_a0 = self.fq(false)?;
+                G2Affine::new_unchecked(x, Fq2::new(y_a0, y_a1))
+            }
+            _ => G2Affine::get_point_from_x_unchecked(x, flag == FLAG_LARGEST)
+                .ok_or_else(|| MoproError::GnarkError("Invalid G2 point".to_string()))?,
+        };
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(MoproError::GnarkError("Invalid G2 point".to_string()));
+        }
+        Ok(G2 {
+            x: vec![to_decimal(point.x.c0), to_decimal(point.x.c1)],
+            y: vec![to_decimal(point.y.c0), to_decimal(point.y.c1)],
+            z: vec!["1".to_string(), "0".to_string()],
+        })
+    }
+}
+
+fn g1_infinity() -> G1 {
+    G1 {
+        x: "0".to_string(),
+        y: "1".to_string(),
+        z: "0".to_string(),
+    }
+}
+
+fn g2_infinity() -> G2 {
+    G2 {
+        x: vec!["0".to_string(), "0".to_string()],
+        y: vec!["1".to_string(), "0".to_string()],
+        z: vec!["0".to_string(), "0".to_string()],
+    }
+}
+
+fn to_decimal<F: PrimeField>(value: F) -> String {
+    value.into_bigint().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+
+    const VK_FILE: &str = "./test-vectors/gnark/cubic_circuit.vk";
+
+    fn encode_raw<P>(point: Affine<P>) -> Vec<u8>
+    where
+        P: SWCurveConfig,
+        P::BaseField: PrimeField,
+    {
+        let mut bytes = point.x.into_bigint().to_bytes_be();
+        bytes.extend(point.y.into_bigint().to_bytes_be());
+        bytes
+    }
+
+    #[test]
+    fn test_point_curve() {
+        let vk = std::fs::read(VK_FILE).unwrap();
+        assert_eq!(point_curve(&vk), Some(GnarkCurve::Bn254));
+        assert_eq!(
+            point_curve(&encode_raw(ark_bls12_381::G1Affine::generator())),
+            Some(GnarkCurve::Bls12_381)
+        );
+        assert_eq!(
+            point_curve(&encode_raw(ark_bls12_377::G1Affine::generator())),
+            Some(GnarkCurve::Bls12_377)
+        );
+        assert_eq!(point_curve(&[0xff; 48]), None);
+    }
+
+    #[test]
+    fn test_ensure_proof_curve_reads_the_key() {
+        let proof = encode_raw(ark_bn254::G1Affine::generator());
+        let mut proof_result = GnarkProofResult {
+            proof: hex::encode(proof),
+            public_inputs: String::new(),
+            curve: GnarkCurve::Bn254,
+        };
+        assert!(ensure_proof_curve(VK_FILE, &proof_result).is_ok());
+
+        // A bn254 key rejects a proof claiming another curve
+        proof_result.curve = GnarkCurve::Bls12_381;
+        assert!(ensure_proof_curve(VK_FILE, &proof_result).is_err());
+
+        // and a proof on another curve, whatever its tag says
+        proof_result.proof = hex::encode(encode_raw(ark_bls12_381::G1Affine::generator()));
+        proof_result.curve = GnarkCurve::Bn254;
+        assert!(ensure_proof_curve(VK_FILE, &proof_result).is_err());
+    }
+
+    #[test]
+    fn test_ccs_header() {
+        let ccs = std::fs::read("./test-vectors/gnark/cubic_circuit.r1cs").unwrap();
+        assert_eq!(circuit_curve(&ccs), Some(GnarkCurve::Bn254));
+        assert_eq!(
+            circuit_schema(&ccs),
+            Some((vec!["Y".to_string()], vec!["X".to_string()]))
+        );
+        // A truncated file is rejected rather than misread
+        assert!(ccs_header(&ccs[..ccs.len() / 2]).is_none());
+    }
+
+    #[test]
+    fn test_witness_names_stay_flat() {
+        let builder = GnarkWitnessBuilder {
+            public: vec!["my_value".to_string()],
+            secret: vec!["P_X".to_string(), "Arr_0".to_string()],
+            values: Mutex::new(BTreeMap::new()),
+        };
+        builder
+            .set_json(r#"{"my_value": "1", "P": {"X": "2"}, "Arr": [3]}"#.to_string())
+            .unwrap();
+        assert_eq!(
+            builder.to_json().unwrap(),
+            r#"{"Arr_0":"3","P_X":"2","my_value":"1"}"#
+        );
+    }
+
+    #[test]
+    fn test_ensure_bn254() {
+        assert!(ensure_bn254(GnarkCurve::Bn254).is_ok());
+        assert!(ensure_bn254(GnarkCurve::Bls12_381).is_err());
+        assert!(ensure_bn254(GnarkCurve::Bls12_377).is_err());
+    }
+}
@@ -27,12 +27,39 @@ pub fn mopro_wasm_hello_world() -> String {
     "Hello, World!".to_string()
 }
 
+/// [`mopro_hello_world`] for C, declared in the header of the `c` platform.
+/// Release the returned string with [`mopro_c_string_free`].
+#[no_mangle]
+pub extern "C" fn mopro_c_hello_world() -> *mut std::ffi::c_char {
+    std::ffi::CString::new(mopro_hello_world()).map_or(std::ptr::null_mut(), |s| s.into_raw())
+}
+
+/// Release a string returned by the C functions.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library, not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn mopro_c_string_free(s: *mut std::ffi::c_char) {
+    if !s.is_null() {
+        drop(unsafe { std::ffi::CString::from_raw(s) });
+    }
+}
+
 #[cfg(test)]
 mod uniffi_tests {
     #[test]
     fn test_mopro_hello_world() {
         assert_eq!(super::mopro_hello_world(), "Hello, World!");
     }
+
+    #[test]
+    fn test_mopro_c_hello_world() {
+        let s = super::mopro_c_hello_world();
+        let hello = unsafe { std::ffi::CStr::from_ptr(s) };
+        assert_eq!(hello.to_str().unwrap(), "Hello, World!");
+        unsafe { super::mopro_c_string_free(s) };
+    }
 }
 
 
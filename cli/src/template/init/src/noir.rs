@@ -10,6 +10,14 @@ use noir_rs::{
     witness::from_vec_str_to_witness_map,
 };
 
+use acvm::{pwg::OpcodeResolutionError, FieldElement};
+use bn254_blackbox_solver::{poseidon_hash, Bn254BlackBoxSolver};
+use nargo::{
+    errors::ExecutionError, foreign_calls::DefaultForeignCallBuilder, ops::execute_program,
+    NargoError,
+};
+use noirc_artifacts::program::ProgramArtifact;
+
 use crate::MoproError;
 
 /// Generates a Noir proof with automatic hash function selection
@@ -93,6 +101,223 @@ pub fn get_noir_verification_key(
     res.map_err(|e| MoproError::NoirError(format!("Get Verification Key error: {}", e)))
 }
 
+/// A Noir proof ready to be verified recursively inside another Noir circuit
+///
+/// `proof_as_fields` and `vk_as_fields` are the proof and verification key
+/// split into BN254 field elements (`0x`-prefixed hex), in the layout expected
+/// by `std::verify_proof`. `vk_hash` is the Poseidon2 hash of `vk_as_fields`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct NoirRecursiveProofResult {
+    pub proof: Vec<u8>,
+    pub vk: Vec<u8>,
+    pub proof_as_fields: Vec<String>,
+    pub vk_as_fields: Vec<String>,
+    pub vk_hash: String,
+}
+
+/// Generates a Noir proof for recursive verification
+///
+/// The SRS is set up for recursion and the proof uses Poseidon as oracle hash,
+/// which is what recursive verification inside a Noir circuit expects. Use
+/// this when proofs are aggregated by another circuit rather than verified
+/// directly.
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn generate_noir_recursive_proof(
+    circuit_path: String,
+    srs_path: Option<String>,
+    inputs: Vec<String>,
+    low_memory_mode: bool,
+) -> Result<NoirRecursiveProofResult, MoproError> {
+    let circuit_bytecode = read_bytecode(&circuit_path)?;
+
+    // Setup the SRS for recursion
+    setup_srs_from_bytecode(circuit_bytecode.as_str(), srs_path.as_deref(), true)
+        .map_err(|e| MoproError::NoirError(format!("Setup SRS error: {}", e)))?;
+
+    let vk = get_ultra_honk_verification_key(circuit_bytecode.as_str(), low_memory_mode)
+        .map_err(|e| MoproError::NoirError(format!("Get Verification Key error: {}", e)))?;
+    // Set up the witness
+    let witness = from_vec_str_to_witness_map(inputs.iter().map(|s| s.as_str()).collect())
+        .map_err(|e| MoproError::NoirError(format!("Parse inputs error: {}", e)))?;
+
+    let proof = prove_ultra_honk(
+        circuit_bytecode.as_str(),
+        witness,
+        vk.clone(),
+        low_memory_mode,
+    )
+    .map_err(|e| MoproError::NoirError(format!("Generate Proof error: {}", e)))?;
+
+    let vk_fields = bytes_to_fields(&vk)?;
+    let vk_hash = poseidon_hash(&vk_fields, false)
+        .map_err(|e| MoproError::NoirError(format!("Hash Verification Key error: {}", e)))?;
+
+    Ok(NoirRecursiveProofResult {
+        proof_as_fields: bytes_to_fields(&proof)?.iter().map(field_to_hex).collect(),
+        vk_as_fields: vk_fields.iter().map(field_to_hex).collect(),
+        vk_hash: field_to_hex(&vk_hash),
+        proof,
+        vk,
+    })
+}
+
+/// Splits serialized barretenberg data into its 32-byte big-endian field elements
+fn bytes_to_fields(bytes: &[u8]) -> Result<Vec<FieldElement>, MoproError> {
+    if bytes.len() % 32 != 0 {
+        return Err(MoproError::NoirError(format!(
+            "Expected a multiple of 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    Ok(bytes
+        .chunks(32)
+        .map(FieldElement::from_be_bytes_reduce)
+        .collect())
+}
+
+fn field_to_hex(field: &FieldElement) -> String {
+    format!("0x{}", field.to_hex())
+}
+
+/// Source position of a failing Noir `assert`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct NoirSourceLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Outcome of solving a Noir circuit's witness without proving
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum NoirExecutionResult {
+    /// All constraints hold. Values are `0x`-prefixed hex field elements,
+    /// `witness` ordered by witness index.
+    Solved {
+        witness: Vec<String>,
+        return_values: Vec<String>,
+    },
+    /// A constraint failed. `message` is the `assert` message when the circuit
+    /// provides one, and `location` is resolved from the circuit debug info.
+    AssertionFailed {
+        message: String,
+        location: Option<NoirSourceLocation>,
+    },
+}
+
+/// Executes a Noir circuit without generating a proof
+///
+/// Only solves the witness, so it is much cheaper than `generate_noir_proof`
+/// and can be used to validate inputs before proving. Inputs use the same
+/// format as `generate_noir_proof`.
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn execute_noir_circuit(
+    circuit_path: String,
+    inputs: Vec<String>,
+) -> Result<NoirExecutionResult, MoproError> {
+    let circuit_txt = std::fs::read(circuit_path)
+        .map_err(|e| MoproError::NoirError(format!("Read circuit error: {}", e)))?;
+    let artifact: ProgramArtifact = serde_json::from_slice(&circuit_txt)
+        .map_err(|e| MoproError::NoirError(format!("Parse circuit error: {}", e)))?;
+
+    let initial_witness = from_vec_str_to_witness_map(inputs.iter().map(|s| s.as_str()).collect())
+        .map_err(|e| MoproError::NoirError(format!("Parse inputs error: {}", e)))?;
+
+    let result = execute_program(
+        &artifact.bytecode,
+        initial_witness,
+        &Bn254BlackBoxSolver(false),
+        &mut DefaultForeignCallBuilder::default().build(),
+    );
+
+    match result {
+        Ok(mut witness_stack) => {
+            let main_witness = witness_stack
+                .pop()
+                .ok_or_else(|| MoproError::NoirError("Empty witness stack".to_string()))?
+                .witness;
+            let return_values = artifact.bytecode.functions[0]
+                .return_values
+                .0
+                .iter()
+                .map(|w| {
+                    main_witness.get(w).map(field_to_hex).ok_or_else(|| {
+                        MoproError::NoirError(format!("Missing return value {}", w.0))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            let witness = main_witness
+                .into_iter()
+                .map(|(_, value)| field_to_hex(&value))
+                .collect();
+            Ok(NoirExecutionResult::Solved {
+                witness,
+                return_values,
+            })
+        }
+        Err(error) if is_assertion_failure(&error) => Ok(NoirExecutionResult::AssertionFailed {
+            message: error
+                .user_defined_failure_message(&artifact.abi.error_types)
+                .map(|message| message.to_string())
+                .unwrap_or_else(|| error.to_string()),
+            location: failure_location(&error, &artifact),
+        }),
+        Err(error) => Err(MoproError::NoirError(format!(
+            "Execute circuit error: {}",
+            error
+        ))),
+    }
+}
+
+/// Whether execution stopped on a constraint the inputs do not satisfy, as
+/// opposed to e.g. missing inputs or an unsolvable opcode
+fn is_assertion_failure(error: &NargoError<FieldElement>) -> bool {
+    matches!(
+        error,
+        NargoError::ExecutionError(
+            ExecutionError::AssertionFailed(..)
+                | ExecutionError::SolvingError(
+                    OpcodeResolutionError::UnsatisfiedConstrain { .. }
+                        | OpcodeResolutionError::BrilligFunctionFailed { .. },
+                    _,
+                )
+        )
+    )
+}
+
+/// Resolves the innermost source location of a failed opcode, if the circuit
+/// was compiled with debug info
+fn failure_location(
+    error: &NargoError<FieldElement>,
+    artifact: &ProgramArtifact,
+) -> Option<NoirSourceLocation> {
+    let call_stack = match error {
+        NargoError::ExecutionError(ExecutionError::AssertionFailed(_, call_stack, _))
+        | NargoError::ExecutionError(ExecutionError::SolvingError(_, Some(call_stack))) => {
+            call_stack
+        }
+        _ => return None,
+    };
+    let opcode = call_stack.last()?;
+    let debug_info = artifact
+        .debug_symbols
+        .debug_infos
+        .get(opcode.acir_function_index)?;
+    let location = debug_info.opcode_location(&opcode.opcode_location)?.pop()?;
+    let file = artifact.file_map.get(&location.file)?;
+
+    let start = (location.span.start() as usize).min(file.source.len());
+    let before = &file.source[..start];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Some(NoirSourceLocation {
+        file: file.path.display().to_string(),
+        line: before.matches('\n').count() as u32 + 1,
+        column: (start - line_start) as u32 + 1,
+    })
+}
+
 /// Generates a Noir proof using Poseidon as oracle hash
 ///
 /// This function uses the Poseidon hash function for better performance.
@@ -223,11 +448,20 @@ fn get_noir_verification_keccak_key(
 }
 
 fn get_bytecode(circuit_path: String) -> String {
-    // Read the JSON manifest of the circuit
-    let circuit_txt = std::fs::read_to_string(circuit_path).unwrap();
-    let circuit: serde_json::Value = serde_json::from_str(&circuit_txt).unwrap();
+    read_bytecode(&circuit_path).unwrap()
+}
 
-    circuit["bytecode"].as_str().unwrap().to_string()
+/// Reads the base64 bytecode from the JSON manifest of the circuit
+fn read_bytecode(circuit_path: &str) -> Result<String, MoproError> {
+    let circuit_txt = std::fs::read_to_string(circuit_path)
+        .map_err(|e| MoproError::NoirError(format!("Read circuit error: {}", e)))?;
+    let circuit: serde_json::Value = serde_json::from_str(&circuit_txt)
+        .map_err(|e| MoproError::NoirError(format!("Parse circuit error: {}", e)))?;
+
+    circuit["bytecode"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| MoproError::NoirError("Circuit has no bytecode".to_string()))
 }
 
 #[cfg(test)]
@@ -236,6 +470,10 @@ mod tests {
     const SRS_FILE: &str = "./test-vectors/noir/noir_multiplier2.srs";
     const VK_FILE: &str = "./test-vectors/noir/noir_multiplier2.vk";
     const VK_POSEIDON_FILE: &str = "./test-vectors/noir/noir_multiplier2_poseidon.vk";
+    const ASSERT_MESSAGE_CIRCUIT_FILE: &str =
+        "./test-vectors/noir/noir_multiplier2_assert_message.json";
+    const VK_POSEIDON_FIELDS_FILE: &str =
+        "./test-vectors/noir/noir_multiplier2_poseidon_vk_fields.json";
 
     use super::*;
 
@@ -646,4 +884,96 @@ mod tests {
         assert!(verify_result_onchain.is_ok());
         assert!(verify_result_onchain.unwrap());
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_noir_recursive_proof_multiplier2() {
+        let result = generate_noir_recursive_proof(
+            MULTIPLIER2_CIRCUIT_FILE.to_string(),
+            Some(SRS_FILE.to_string()),
+            vec!["3".to_string(), "5".to_string()],
+            false,
+        )
+        .unwrap();
+
+        // The vk and its field encoding match the ones written by bb
+        let expected_fields: Vec<String> =
+            serde_json::from_str(&std::fs::read_to_string(VK_POSEIDON_FIELDS_FILE).unwrap())
+                .unwrap();
+        assert_eq!(result.vk, std::fs::read(VK_POSEIDON_FILE).unwrap());
+        assert_eq!(result.vk_as_fields, expected_fields);
+        assert_eq!(result.proof_as_fields.len() * 32, result.proof.len());
+        assert!(result.vk_hash.starts_with("0x") && result.vk_hash.len() == 66);
+
+        assert!(verify_noir_proof_with_poseidon(
+            MULTIPLIER2_CIRCUIT_FILE.to_string(),
+            result.proof,
+            result.vk,
+            false,
+        ));
+    }
+
+    #[test]
+    fn test_execute_noir_circuit_multiplier2() {
+        let result = execute_noir_circuit(
+            MULTIPLIER2_CIRCUIT_FILE.to_string(),
+            vec!["3".to_string(), "5".to_string(), "15".to_string()],
+        )
+        .unwrap();
+        match result {
+            NoirExecutionResult::Solved { witness, .. } => {
+                assert_eq!(
+                    witness[..3],
+                    [
+                        field_to_hex(&3u128.into()),
+                        field_to_hex(&5u128.into()),
+                        field_to_hex(&15u128.into())
+                    ]
+                );
+            }
+            NoirExecutionResult::AssertionFailed { message, .. } => {
+                panic!("unexpected assertion failure: {message}")
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_noir_circuit_multiplier2_unsatisfied() {
+        let result = execute_noir_circuit(
+            MULTIPLIER2_CIRCUIT_FILE.to_string(),
+            vec!["3".to_string(), "5".to_string(), "16".to_string()],
+        )
+        .unwrap();
+        assert!(matches!(
+            result,
+            NoirExecutionResult::AssertionFailed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_execute_noir_circuit_assert_message() {
+        let result = execute_noir_circuit(
+            ASSERT_MESSAGE_CIRCUIT_FILE.to_string(),
+            vec!["3".to_string(), "5".to_string(), "16".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            NoirExecutionResult::AssertionFailed {
+                message: "a * b must equal result".to_string(),
+                location: Some(NoirSourceLocation {
+                    file: "src/main.nr".to_string(),
+                    line: 2,
+                    column: 5,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_execute_noir_circuit_multiplier2_missing_input() {
+        let result =
+            execute_noir_circuit(MULTIPLIER2_CIRCUIT_FILE.to_string(), vec!["3".to_string()]);
+        assert!(matches!(result, Err(MoproError::NoirError(_))));
+    }
 }
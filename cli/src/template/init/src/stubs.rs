@@ -4,12 +4,14 @@ macro_rules! circom_stub {
         mod circom_stub {
             use crate::error::MoproError;
 
+            #[derive(Debug, Clone)]
             #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
             pub struct CircomProofResult {
                 pub proof: CircomProof,
                 pub inputs: Vec<String>,
             }
 
+            #[derive(Debug, Clone)]
             #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
             pub struct G1 {
                 pub x: String,
@@ -17,6 +19,7 @@ macro_rules! circom_stub {
                 pub z: String,
             }
 
+            #[derive(Debug, Clone)]
             #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
             pub struct G2 {
                 pub x: Vec<String>,
@@ -24,6 +27,7 @@ macro_rules! circom_stub {
                 pub z: Vec<String>,
             }
 
+            #[derive(Debug, Clone)]
             #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
             pub struct CircomProof {
                 pub a: G1,
@@ -131,6 +135,55 @@ macro_rules! noir_stub {
             }
 
 
+            #[derive(Debug, Clone, Default)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+            pub struct NoirRecursiveProofResult {
+                pub proof: Vec<u8>,
+                pub vk: Vec<u8>,
+                pub proof_as_fields: Vec<String>,
+                pub vk_as_fields: Vec<String>,
+                pub vk_hash: String,
+            }
+
+            #[cfg_attr(feature = "uniffi", uniffi::export)]
+            pub fn generate_noir_recursive_proof(
+                _circuit_path: String,
+                _srs_path: Option<String>,
+                _inputs: Vec<String>,
+                _low_memory_mode: bool,
+            ) -> Result<NoirRecursiveProofResult, MoproError> {
+                panic!("Noir is not enabled in this build. Please select \"noir\" adapter when initializing the project.");
+            }
+
+            #[derive(Debug, Clone, PartialEq)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+            pub struct NoirSourceLocation {
+                pub file: String,
+                pub line: u32,
+                pub column: u32,
+            }
+
+            #[derive(Debug, Clone, PartialEq)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+            pub enum NoirExecutionResult {
+                Solved {
+                    witness: Vec<String>,
+                    return_values: Vec<String>,
+                },
+                AssertionFailed {
+                    message: String,
+                    location: Option<NoirSourceLocation>,
+                },
+            }
+
+            #[cfg_attr(feature = "uniffi", uniffi::export)]
+            pub fn execute_noir_circuit(
+                _circuit_path: String,
+                _inputs: Vec<String>,
+            ) -> Result<NoirExecutionResult, MoproError> {
+                panic!("Noir is not enabled in this build. Please select \"noir\" adapter when initializing the project.");
+            }
+
             #[cfg_attr(feature = "uniffi", uniffi::export)]
             pub fn get_noir_verification_key(
                 _circuit_path: String,
@@ -145,7 +198,9 @@ macro_rules! noir_stub {
 
         }
         pub use noir_stub::{
-            generate_noir_proof, get_noir_verification_key, verify_noir_proof,
+            execute_noir_circuit, generate_noir_proof, generate_noir_recursive_proof,
+            get_noir_verification_key, verify_noir_proof, NoirExecutionResult,
+            NoirRecursiveProofResult, NoirSourceLocation,
         };
     };
 }
@@ -156,11 +211,20 @@ macro_rules! gnark_stub {
         mod gnark_stub {
             use crate::error::MoproError;
 
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+            pub enum GnarkCurve {
+                Bn254,
+                Bls12_381,
+                Bls12_377,
+            }
+
             #[derive(Debug, Clone)]
             #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
             pub struct GnarkProofResult {
                 pub proof: String,
                 pub public_inputs: String,
+                pub curve: GnarkCurve,
             }
 
             #[cfg_attr(feature = "uniffi", uniffi::export)]
@@ -168,6 +232,7 @@ macro_rules! gnark_stub {
                 _r1cs_path: String,
                 _pk_path: String,
                 _witness_json: String,
+                _curve: GnarkCurve,
             ) -> Result<GnarkProofResult, MoproError> {
                 panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
             }
@@ -180,7 +245,72 @@ macro_rules! gnark_stub {
             ) -> Result<bool, MoproError> {
                 panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
             }
+
+            #[derive(Debug, Clone)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+            pub struct GnarkVerifyingKey {
+                pub alpha: crate::G1,
+                pub beta: crate::G2,
+                pub gamma: crate::G2,
+                pub delta: crate::G2,
+                pub ic: Vec<crate::G1>,
+                pub curve: String,
+            }
+
+            #[cfg_attr(feature = "uniffi", uniffi::export)]
+            pub fn convert_gnark_proof(
+                _proof_result: GnarkProofResult,
+            ) -> Result<crate::CircomProofResult, MoproError> {
+                panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+            }
+
+            #[cfg_attr(feature = "uniffi", uniffi::export)]
+            pub fn convert_gnark_verifying_key(
+                _vk_path: String,
+            ) -> Result<GnarkVerifyingKey, MoproError> {
+                panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+            }
+
+            #[derive(Debug)]
+            #[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+            pub struct GnarkWitnessBuilder {}
+
+            #[cfg_attr(feature = "uniffi", uniffi::export)]
+            impl GnarkWitnessBuilder {
+                #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+                pub fn new(_r1cs_path: String) -> Result<std::sync::Arc<Self>, MoproError> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn public_names(&self) -> Vec<String> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn secret_names(&self) -> Vec<String> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn set(&self, _name: String, _value: String) -> Result<(), MoproError> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn set_json(&self, _witness_json: String) -> Result<(), MoproError> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn missing_names(&self) -> Vec<String> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+
+                pub fn to_json(&self) -> Result<String, MoproError> {
+                    panic!("Gnark is not enabled in this build. Please select \"gnark\" adapter when initializing the project.");
+                }
+            }
         }
-        pub use gnark_stub::{generate_gnark_proof, verify_gnark_proof, GnarkProofResult};
+        pub use gnark_stub::{
+            convert_gnark_proof, convert_gnark_verifying_key, generate_gnark_proof,
+            verify_gnark_proof, GnarkCurve, GnarkProofResult, GnarkVerifyingKey,
+            GnarkWitnessBuilder,
+        };
     };
 }
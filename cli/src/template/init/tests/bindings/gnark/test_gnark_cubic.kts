@@ -9,7 +9,7 @@ try {
     val witnessJson = "{\"X\": \"3\", \"Y\": \"35\"}"
 
     // Generate proof
-    val proofResult = generateGnarkProof(r1csPath, pkPath, witnessJson)
+    val proofResult = generateGnarkProof(r1csPath, pkPath, witnessJson, GnarkCurve.BN254)
     assert(proofResult.proof.isNotEmpty()) { "Proof should not be empty" }
     assert(proofResult.publicInputs.isNotEmpty()) { "Public inputs should not be empty" }
 
@@ -17,6 +17,23 @@ try {
     val isValid = verifyGnarkProof(r1csPath, vkPath, proofResult)
     assert(isValid) { "Proof is invalid" }
 
+    // The verifying key's curve must match the proof's
+    val mismatched = proofResult.copy(curve = GnarkCurve.BLS12_381)
+    val rejected = try {
+        verifyGnarkProof(r1csPath, vkPath, mismatched)
+        false
+    } catch (e: MoproException) {
+        true
+    }
+    assert(rejected) { "Proof with a mismatched curve should be rejected" }
+
+    // Typed witness
+    val builder = GnarkWitnessBuilder(r1csPath)
+    builder.set("X", "3")
+    builder.set("Y", "35")
+    val builtProof = generateGnarkProof(r1csPath, pkPath, builder.toJson(), GnarkCurve.BN254)
+    assert(verifyGnarkProof(r1csPath, vkPath, builtProof)) { "Built witness proof is invalid" }
+
 } catch (e: Exception) {
     println(e)
     throw e
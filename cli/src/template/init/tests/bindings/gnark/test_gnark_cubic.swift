@@ -11,7 +11,7 @@ do {
 
     // Generate Proof
     let proofResult = try generateGnarkProof(
-        r1csPath: r1csPath, pkPath: pkPath, witnessJson: witnessJson)
+        r1csPath: r1csPath, pkPath: pkPath, witnessJson: witnessJson, curve: .bn254)
     assert(!proofResult.proof.isEmpty, "Proof should not be empty")
     assert(!proofResult.publicInputs.isEmpty, "Public inputs should not be empty")
 
@@ -20,6 +20,28 @@ do {
         r1csPath: r1csPath, vkPath: vkPath, proofResult: proofResult)
     assert(isValid, "Proof verification should succeed")
 
+    // The verifying key's curve must match the proof's
+    var mismatched = proofResult
+    mismatched.curve = .bls12381
+    let rejected: Bool
+    do {
+        _ = try verifyGnarkProof(r1csPath: r1csPath, vkPath: vkPath, proofResult: mismatched)
+        rejected = false
+    } catch {
+        rejected = true
+    }
+    assert(rejected, "Proof with a mismatched curve should be rejected")
+
+    // Typed witness
+    let builder = try GnarkWitnessBuilder(r1csPath: r1csPath)
+    try builder.set(name: "X", value: "3")
+    try builder.set(name: "Y", value: "35")
+    let builtProof = try generateGnarkProof(
+        r1csPath: r1csPath, pkPath: pkPath, witnessJson: try builder.toJson(), curve: .bn254)
+    let isBuiltValid = try verifyGnarkProof(
+        r1csPath: r1csPath, vkPath: vkPath, proofResult: builtProof)
+    assert(isBuiltValid, "Built witness proof is invalid")
+
 } catch let error as MoproError {
     print("MoproError: \(error)")
     throw error
@@ -233,7 +233,7 @@ extension ContentView {
 
       // Generate Proof
       let generateProofResult = try generateGnarkProof(
-        r1csPath: gnarkR1csPath, pkPath: gnarkPkPath, witnessJson: witnessJson)
+        r1csPath: gnarkR1csPath, pkPath: gnarkPkPath, witnessJson: witnessJson, curve: .bn254)
       assert(!generateProofResult.proof.isEmpty, "Proof should not be empty")
       assert(!generateProofResult.publicInputs.isEmpty, "Public inputs should not be empty")
 
@@ -266,7 +266,7 @@ extension ContentView {
 
       let isValid = try verifyGnarkProof(
         r1csPath: gnarkR1csPath, vkPath: gnarkVkPath,
-        proofResult: GnarkProofResult(proof: proof, publicInputs: publicInputs))
+        proofResult: GnarkProofResult(proof: proof, publicInputs: publicInputs, curve: .bn254))
       let end = CFAbsoluteTimeGetCurrent()
       let timeTaken = end - start
 
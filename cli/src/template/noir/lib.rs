@@ -2,7 +2,11 @@
 
 // Module containing the Noir circuit logic (Multiplier2)
 mod noir;
-pub use noir::{generate_noir_proof, get_noir_verification_key, verify_noir_proof,};
+pub use noir::{
+    execute_noir_circuit, generate_noir_proof, generate_noir_recursive_proof,
+    get_noir_verification_key, verify_noir_proof, NoirExecutionResult, NoirRecursiveProofResult,
+    NoirSourceLocation,
+};
 
 #[cfg(test)]
 mod noir_tests {
@@ -8,10 +8,7 @@ use crate::config::{read_config, write_config, Config, UpdateConfig};
 use crate::constants::Platform;
 use crate::print::print_update_success_message;
 use crate::style::{print_gray_items, print_green_bold};
-use mopro_ffi::app_config::constants::{
-    ANDROID_JNILIBS_DIR, ANDROID_KT_FILE, ANDROID_PACKAGE_NAME, ANDROID_UNIFFI_DIR, IOS_SWIFT_FILE,
-    IOS_XCFRAMEWORKS_DIR,
-};
+use mopro_ffi::app_config::constants::{ANDROID_JNILIBS_DIR, ANDROID_UNIFFI_DIR};
 
 pub fn update_bindings(
     arg_src: &Option<String>,
@@ -34,8 +31,8 @@ pub fn update_bindings(
         let dest_path = PathBuf::from(dest_str);
         let platform_paths = detect_platform_paths(&dest_path)?;
         for (platform, platform_dest) in platform_paths {
-            verify_source(&src_dir, platform)?;
-            update_platform(&src_dir, &platform_dest, platform)?;
+            verify_source(&config, &src_dir, platform)?;
+            update_platform(&config, &src_dir, &platform_dest, platform)?;
             if !no_prompt {
                 maybe_store_dest(&mut config, platform, &platform_dest)?;
             }
@@ -48,8 +45,7 @@ pub fn update_bindings(
             Platform::ReactNative,
             Platform::Web,
         ] {
-            let binding_dir_name = platform.binding_dir();
-            let platform_bindings_dir = src_dir.join(binding_dir_name);
+            let platform_bindings_dir = src_dir.join(config.binding_dir(platform));
             if !platform_bindings_dir.exists() {
                 continue;
             }
@@ -63,14 +59,18 @@ pub fn update_bindings(
                     Platform::Flutter => None, // TODO: Add Flutter dest
                     Platform::ReactNative => u.react_native_dest.as_ref(),
                     Platform::Web => None,
+                    Platform::Python => None,
+                    Platform::Jvm => None,
+                    Platform::Node => None,
+                    Platform::C => None,
                 })
                 .map(PathBuf::from);
 
             if let Some(dest) = dest_root {
-                update_platform(&src_dir, &dest, platform)?;
+                update_platform(&config, &src_dir, &dest, platform)?;
                 updated_any = true;
             } else {
-                let did_update = update_platform(&src_dir, &src_dir, platform)?;
+                let did_update = update_platform(&config, &src_dir, &src_dir, platform)?;
                 updated_any = updated_any || did_update;
             }
         }
@@ -83,7 +83,7 @@ pub fn update_bindings(
             let dest_path = PathBuf::from(dest_input);
             let platform_paths = detect_platform_paths(&dest_path)?;
             for (platform, platform_dest) in platform_paths {
-                verify_source(&src_dir, platform)?;
+                verify_source(&config, &src_dir, platform)?;
                 let proceed = Confirm::with_theme(&ColorfulTheme::default())
                     .with_prompt(format!(
                         "Detected {} project.  Proceed?",
@@ -94,7 +94,7 @@ pub fn update_bindings(
                 if !proceed {
                     continue;
                 }
-                update_platform(&src_dir, &platform_dest, platform)?;
+                update_platform(&config, &src_dir, &platform_dest, platform)?;
                 let remember = if no_prompt {
                     false
                 } else {
@@ -119,12 +119,11 @@ pub fn update_bindings(
     Ok(())
 }
 
-fn verify_source(src_dir: &Path, platform: Platform) -> Result<()> {
-    let binding_dir = src_dir.join(platform.binding_dir());
-    if !binding_dir.exists() {
+fn verify_source(config: &Config, src_dir: &Path, platform: Platform) -> Result<()> {
+    let binding_dir_name = config.binding_dir(platform);
+    if !src_dir.join(&binding_dir_name).exists() {
         return Err(anyhow!(
-            "Source directory does not contain {}",
-            platform.binding_dir()
+            "Source directory does not contain {binding_dir_name}"
         ));
     }
     Ok(())
@@ -189,8 +188,14 @@ fn is_android_project(path: &Path) -> bool {
     has_manifest
 }
 
-fn update_platform(src_root: &Path, dest_root: &Path, platform: Platform) -> Result<bool> {
-    let binding_dir_name = platform.binding_dir();
+fn update_platform(
+    config: &Config,
+    src_root: &Path,
+    dest_root: &Path,
+    platform: Platform,
+) -> Result<bool> {
+    let binding_dir_name = config.binding_dir(platform);
+    let binding_dir_name = binding_dir_name.as_str();
     let platform_bindings_dir = src_root.join(binding_dir_name);
 
     if !platform_bindings_dir.exists() {
@@ -205,26 +210,28 @@ fn update_platform(src_root: &Path, dest_root: &Path, platform: Platform) -> Res
     let mut updated_paths = Vec::new();
     match platform {
         Platform::Ios => {
+            let names = config.ios_bindings_names();
             updated_paths.extend(update_folder(
-                &platform_bindings_dir.join(IOS_XCFRAMEWORKS_DIR),
+                &platform_bindings_dir.join(&names.xcframework),
                 dest_root,
-                IOS_XCFRAMEWORKS_DIR,
+                &names.xcframework,
                 false,
             )?);
             updated_paths.extend(update_file(
-                &platform_bindings_dir.join(IOS_SWIFT_FILE),
+                &platform_bindings_dir.join(&names.swift_file),
                 dest_root,
-                IOS_SWIFT_FILE,
+                &names.swift_file,
             )?);
         }
         Platform::Android => {
+            let names = config.android_bindings_names();
             let jnilib_path = platform_bindings_dir.join(ANDROID_JNILIBS_DIR);
             let kotlin_path = platform_bindings_dir
                 .join(ANDROID_UNIFFI_DIR)
-                .join(ANDROID_PACKAGE_NAME)
-                .join(ANDROID_KT_FILE);
+                .join(&names.package_name)
+                .join(&names.kt_file);
 
-            updated_paths.extend(update_file(&kotlin_path, dest_root, ANDROID_KT_FILE)?);
+            updated_paths.extend(update_file(&kotlin_path, dest_root, &names.kt_file)?);
             updated_paths.extend(update_folder(
                 &jnilib_path,
                 dest_root,
@@ -249,7 +256,7 @@ fn update_platform(src_root: &Path, dest_root: &Path, platform: Platform) -> Res
                 false,
             )?);
         }
-        Platform::Web => {
+        Platform::Web | Platform::Python | Platform::Jvm | Platform::Node | Platform::C => {
             updated_paths.extend(update_folder(
                 &platform_bindings_dir,
                 dest_root,
@@ -348,7 +355,7 @@ fn maybe_store_dest(config: &mut Config, platform: Platform, dest: &Path) -> Res
         Platform::Android => &update_cfg.android_dest,
         Platform::Flutter => &None, // TODO: fix flutter update
         Platform::ReactNative => &update_cfg.react_native_dest,
-        Platform::Web => &None,
+        Platform::Web | Platform::Python | Platform::Jvm | Platform::Node | Platform::C => &None,
     };
     if existing.is_none() {
         let remember = Confirm::with_theme(&ColorfulTheme::default())
@@ -374,6 +381,10 @@ impl UpdateConfigExt for UpdateConfig {
             Platform::Flutter => {} // TODO: fix flutter update
             Platform::ReactNative => self.react_native_dest = Some(dest),
             Platform::Web => {}
+            Platform::Python => {}
+            Platform::Jvm => {}
+            Platform::Node => {}
+            Platform::C => {}
         }
     }
 }
@@ -1,46 +1,108 @@
 use anyhow::Context;
 use camino::Utf8Path;
-use std::collections::HashMap;
+use object::elf::{FileHeader32, FileHeader64, PT_LOAD};
+use object::read::elf::{FileHeader, ProgramHeader};
+use object::{Endianness, FileKind};
 use std::fs;
 use std::io::{Error, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use uniffi::{generate, GenerateOptions, TargetLanguage};
 
-use crate::app_config::{project_name_from_toml, PlatformBuilder};
+use crate::app_config::{
+    project_name_from_toml, raw_project_name_from_toml, version_from_toml, PlatformBuilder,
+};
 
-use super::cleanup_tmp_local;
 use super::constants::{
-    AndroidArch, AndroidPlatform, Arch, Mode, ANDROID_BINDINGS_DIR, ANDROID_KT_FILE,
-    ANDROID_PACKAGE_NAME, ARCH_ARM_64_V8, ARCH_ARM_V7_ABI, ARCH_I686, ARCH_X86_64,
+    AndroidArch, AndroidPlatform, Arch, Mode, ANDROID_AGP_VERSION, ANDROID_BINDINGS_DIR,
+    ANDROID_COMPILE_SDK, ANDROID_JNILIBS_DIR, ANDROID_KOTLIN_VERSION, ANDROID_KT_FILE,
+    ANDROID_MAVEN_DIR, ANDROID_MAVEN_GROUP, ANDROID_PACKAGE_NAME, ANDROID_SYMBOLS_DIR,
+    ANDROID_UNIFFI_DIR, ARCH_ARM_64_V8, ARCH_ARM_V7_ABI, ARCH_I686, ARCH_X86_64,
 };
 use super::install_arch;
 use super::install_ndk;
-use super::mktemp_local;
+use super::plan::BuildPlan;
+pub use super::ArchBuildConfig;
+use super::{apply_arch_override, ArchOverrides};
+use super::{arch_target_dir, build_archs};
+use super::{cleanup_tmp_local, command, move_bindings_into_place};
+use super::{create_staging_dir, staging_dir};
+use super::{install_arch_command, install_ndk_command};
 
 // Maintained for backwards compatibility
 #[inline]
-pub fn build() {
+pub fn build() -> anyhow::Result<()> {
     super::build_from_env::<AndroidPlatform>()
 }
 
-/// Per-architecture build overrides a caller can inject into the `cargo ndk`
-/// invocation (e.g. the CLI, on behalf of an adapter that needs special linking).
-/// Keyed in [`AndroidBindingsParams::arch_overrides`] by target triple.
-#[derive(Default, Clone)]
-pub struct ArchBuildConfig {
-    /// Extra environment variables for this arch's `cargo ndk` invocation.
-    pub extra_env: Vec<(String, String)>,
-    /// Flags appended to `RUSTFLAGS` for this arch (e.g. `-Clinker=<wrapper>`).
-    pub extra_rustflags: Vec<String>,
+/// Names of the generated Android artifacts. Give each library its own package
+/// to ship several mopro libraries in one app.
+#[derive(Clone, Debug)]
+pub struct AndroidBindingsNames {
+    pub bindings_dir: String,
+    /// Kotlin package under `uniffi.`, e.g. `mopro` for `uniffi.mopro`.
+    pub package_name: String,
+    pub kt_file: String,
+}
+
+impl Default for AndroidBindingsNames {
+    fn default() -> Self {
+        Self {
+            bindings_dir: ANDROID_BINDINGS_DIR.to_string(),
+            package_name: ANDROID_PACKAGE_NAME.to_string(),
+            kt_file: ANDROID_KT_FILE.to_string(),
+        }
+    }
+}
+
+/// Versions written to the `build.gradle.kts` of the library module.
+#[derive(Clone, Debug)]
+pub struct AndroidGradleVersions {
+    /// Android Gradle Plugin, `com.android.library`.
+    pub agp: String,
+    /// Kotlin Gradle plugin, `org.jetbrains.kotlin.android`.
+    pub kotlin: String,
+    pub compile_sdk: u32,
+}
+
+impl Default for AndroidGradleVersions {
+    fn default() -> Self {
+        Self {
+            agp: ANDROID_AGP_VERSION.to_string(),
+            kotlin: ANDROID_KOTLIN_VERSION.to_string(),
+            compile_sdk: ANDROID_COMPILE_SDK,
+        }
+    }
 }
 
+/// Gradle wrapper shipped with the library module, so that it builds without
+/// a system Gradle.
+const GRADLE_WRAPPER_FILES: [(&str, &[u8]); 4] = [
+    (
+        "gradlew",
+        include_bytes!("template/android_gradle_wrapper/gradlew"),
+    ),
+    (
+        "gradlew.bat",
+        include_bytes!("template/android_gradle_wrapper/gradlew.bat"),
+    ),
+    (
+        "gradle/wrapper/gradle-wrapper.jar",
+        include_bytes!("template/android_gradle_wrapper/gradle/wrapper/gradle-wrapper.jar"),
+    ),
+    (
+        "gradle/wrapper/gradle-wrapper.properties",
+        include_bytes!("template/android_gradle_wrapper/gradle/wrapper/gradle-wrapper.properties"),
+    ),
+];
+
 /// Generic knobs that let a caller tailor the Android build without `mopro-ffi`
 /// knowing about any specific adapter. Defaults to a plain, unmodified build.
 #[derive(Default)]
 pub struct AndroidBindingsParams {
+    pub names: AndroidBindingsNames,
     /// Per-target-triple overrides, keyed by [`AndroidArch::as_str`].
-    pub arch_overrides: HashMap<String, ArchBuildConfig>,
+    pub arch_overrides: ArchOverrides,
     /// `--platform` (min Android API) passed to `cargo ndk`; cargo-ndk's own
     /// default is used when `None`.
     pub min_sdk_version: Option<u32>,
@@ -48,6 +110,22 @@ pub struct AndroidBindingsParams {
     /// shipped lib is produced by a custom linker that strips the `.symtab`
     /// uniffi-bindgen reads (e.g. Zig); the shipped jniLibs are left untouched.
     pub relink_with_ndk_for_bindgen: bool,
+    /// Run the module's Gradle wrapper to publish the `.aar` and its POM to the
+    /// `maven/` repository inside the bindings. Needs a JDK and the Android SDK;
+    /// otherwise the module can be built later by hand.
+    pub publish_aar: bool,
+    /// Strip the shipped jniLibs, keeping their debug info in
+    /// `symbols/<abi>/lib<name>.so.debug` to symbolicate crash reports.
+    pub split_debug_info: bool,
+    /// Page size the libraries are linked for, e.g. `16384` for devices with
+    /// 16 KB pages. The build fails if a `LOAD` segment of a shipped library is
+    /// aligned below it. The linker default is kept when `None`.
+    pub page_size: Option<u64>,
+    /// Number of architectures built at once, each in its own target dir.
+    /// `0` or `1` builds them one after another.
+    pub jobs: usize,
+    /// Plugin and SDK versions of the library module.
+    pub gradle: AndroidGradleVersions,
 }
 
 impl PlatformBuilder for AndroidPlatform {
@@ -61,12 +139,12 @@ impl PlatformBuilder for AndroidPlatform {
         params: Self::Params,
     ) -> anyhow::Result<PathBuf> {
         let uniffi_style_identifier = project_name_from_toml(project_dir)
-            .expect("Failed to get project name from Cargo.toml");
+            .context("Failed to get project name from Cargo.toml")?;
 
-        // Names for the files that will be outputted (can be changed)
-        let binding_dir_name = ANDROID_BINDINGS_DIR;
-        let out_android_package_name = ANDROID_PACKAGE_NAME;
-        let out_android_kt_file_name = ANDROID_KT_FILE;
+        // Names for the files that will be outputted
+        let binding_dir_name = params.names.bindings_dir.as_str();
+        let out_android_package_name = params.names.package_name.as_str();
+        let out_android_kt_file_name = params.names.kt_file.as_str();
 
         // Names for the generated files by uniffi
         let lib_name = format!("lib{}.so", &uniffi_style_identifier);
@@ -78,20 +156,24 @@ impl PlatformBuilder for AndroidPlatform {
 
         // Paths for the generated files
         let build_dir = Path::new(&project_dir).join("build");
-        let work_dir = mktemp_local(&build_dir);
+        let work_dir = create_staging_dir(&build_dir, binding_dir_name)?;
         let bindings_out = work_dir.join(binding_dir_name);
         let bindings_dest = Path::new(&project_dir).join(binding_dir_name);
 
-        install_ndk();
-        let mut latest_out_lib_path = PathBuf::new();
-        let mut bindgen_arch: Option<AndroidArch> = None;
-        for arch in target_archs {
-            latest_out_lib_path =
-                build_for_arch(arch, &lib_name, &build_dir, &bindings_out, mode, &params).context(
-                    format!("Failed to build for architecture: {}", arch.as_str()),
-                )?;
-            bindgen_arch.get_or_insert(arch);
+        install_ndk()?;
+        for arch in &target_archs {
+            install_arch(arch.as_str().to_string())?;
+        }
+        let out_lib_paths = build_archs(&target_archs, params.jobs, |arch| {
+            build_for_arch(arch, &lib_name, &build_dir, &bindings_out, &mode, &params).context(
+                format!("Failed to build for architecture: {}", arch.as_str()),
+            )
+        })?;
+        if let Some(page_size) = params.page_size {
+            check_page_alignment(&bindings_out.join(ANDROID_JNILIBS_DIR), page_size)?;
         }
+        let latest_out_lib_path = out_lib_paths.last().cloned().unwrap_or_default();
+        let bindgen_arch = target_archs.first().copied();
 
         // A custom linker can strip the `.symtab` uniffi-bindgen needs, so when
         // asked, generate bindings from a separate NDK-linked build.
@@ -105,21 +187,150 @@ impl PlatformBuilder for AndroidPlatform {
         };
 
         generate_android_bindings(&bindgen_lib_path, &bindings_out)
-            .expect("Failed to generate bindings");
+            .context("Failed to generate bindings")?;
 
         reformat_kotlin_package(
             gen_android_module_name,
             &gen_android_kt_file_name,
             out_android_package_name,
-            &out_android_kt_file_name,
+            out_android_kt_file_name,
             &bindings_out,
         )
-        .expect("Failed to reformat generated Kotlin package");
+        .context("Failed to reformat generated Kotlin package")?;
+
+        write_gradle_module(project_dir, &bindings_out, &params)
+            .context("Failed to write Gradle library module")?;
+        if params.publish_aar {
+            publish_aar(&bindings_out)?;
+        }
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir)?;
 
-        move_bindings(&bindings_out, &bindings_dest);
-        cleanup_tmp_local(&build_dir);
+        Ok(bindings_dest)
+    }
 
-        Ok(bindings_out)
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let names = &params.names;
+        let lib_name = format!("lib{uniffi_style_identifier}.so");
+        let build_dir = project_dir.join("build");
+        let bindings_dest = project_dir.join(&names.bindings_dir);
+        let bindings_out = staging_dir(&build_dir, &names.bindings_dir).join(&names.bindings_dir);
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        plan.command(
+            "Install cargo-ndk, unless already installed",
+            &install_ndk_command(),
+            vec![],
+            vec![],
+        );
+        let mut latest_out_lib_path = None;
+        for &arch in target_archs {
+            let arch_str = arch.as_str();
+            let out_lib_path = arch_target_dir(&build_dir, arch_str, params.jobs)
+                .join(arch_str)
+                .join(mode.as_str())
+                .join(&lib_name);
+            plan.command(
+                format!("Install the {arch_str} target"),
+                &install_arch_command(arch_str),
+                vec![],
+                vec![],
+            );
+            plan.command(
+                format!("Build for {arch_str}"),
+                &ndk_build_command(arch, &build_dir, &bindings_out, &mode, params),
+                vec![project_dir.to_path_buf()],
+                vec![
+                    out_lib_path.clone(),
+                    bindings_out
+                        .join(ANDROID_JNILIBS_DIR)
+                        .join(abi_folder(arch))
+                        .join(&lib_name),
+                ],
+            );
+            if params.split_debug_info {
+                let shipped_lib = bindings_out
+                    .join(ANDROID_JNILIBS_DIR)
+                    .join(abi_folder(arch))
+                    .join(&lib_name);
+                let debug_file = debug_file_path(&bindings_out, arch, &lib_name);
+                plan.command(
+                    format!("Save the debug info of {arch_str}"),
+                    &only_keep_debug_command(&shipped_lib, &debug_file),
+                    vec![shipped_lib.clone()],
+                    vec![debug_file.clone()],
+                );
+                plan.command(
+                    format!("Strip the {arch_str} library"),
+                    &strip_command(&shipped_lib, &debug_file),
+                    vec![shipped_lib.clone(), debug_file],
+                    vec![shipped_lib],
+                );
+            }
+            latest_out_lib_path = Some(out_lib_path);
+        }
+        if let Some(page_size) = params.page_size {
+            let jni_libs = bindings_out.join(ANDROID_JNILIBS_DIR);
+            plan.step(
+                format!(
+                    "Check the LOAD segments of the libraries are aligned to {page_size} bytes"
+                ),
+                vec![jni_libs],
+                vec![],
+            );
+        }
+        let mut bindgen_lib = latest_out_lib_path.context("No target architectures provided")?;
+        if params.relink_with_ndk_for_bindgen {
+            let arch = target_archs[0];
+            bindgen_lib = bindgen_lib_path(arch, &lib_name, &build_dir);
+            plan.command(
+                format!(
+                    "Build {} with the NDK linker for binding generation",
+                    arch.as_str()
+                ),
+                &bindgen_lib_command(arch, &build_dir, params),
+                vec![project_dir.to_path_buf()],
+                vec![bindgen_lib.clone()],
+            );
+        }
+        plan.step(
+            "Generate the Kotlin bindings with UniFFI",
+            vec![bindgen_lib],
+            vec![bindings_out
+                .join(ANDROID_UNIFFI_DIR)
+                .join(&names.package_name)
+                .join(&names.kt_file)],
+        );
+        plan.step(
+            format!(
+                "Write the Gradle library module (AGP {}, Kotlin {}, compileSdk {})",
+                params.gradle.agp, params.gradle.kotlin, params.gradle.compile_sdk
+            ),
+            vec![project_dir.join("Cargo.toml")],
+            vec![
+                bindings_out.join("settings.gradle.kts"),
+                bindings_out.join("build.gradle.kts"),
+                bindings_out.join("AndroidManifest.xml"),
+                bindings_out.join(GRADLE_WRAPPER),
+            ],
+        );
+        if params.publish_aar {
+            plan.command(
+                "Publish the AAR",
+                &publish_aar_command(&bindings_out),
+                vec![bindings_out.clone()],
+                vec![bindings_out.join(ANDROID_MAVEN_DIR)],
+            );
+        }
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
     }
 }
 
@@ -128,49 +339,24 @@ fn build_for_arch(
     lib_name: &str,
     build_dir: &Path,
     bindings_out: &Path,
-    mode: Mode,
+    mode: &Mode,
     params: &AndroidBindingsParams,
 ) -> anyhow::Result<PathBuf> {
     let arch_str = arch.as_str();
-    install_arch(arch_str.to_string());
-    let cpp_lib_dest = bindings_out.join("jniLibs");
-
-    let mut build_cmd = Command::new("cargo");
-    build_cmd.arg("ndk").arg("-t").arg(arch_str);
-    if let Some(min_sdk) = params.min_sdk_version {
-        build_cmd.arg("--platform").arg(min_sdk.to_string());
-    }
-    build_cmd
-        .arg("build")
-        .arg("--link-libcxx-shared")
-        .arg("--lib");
-    if mode == Mode::Release {
-        build_cmd.arg("--release");
-    }
-
-    apply_arch_config(&mut build_cmd, params.arch_overrides.get(arch_str));
-
-    let status = build_cmd
-        .env("CARGO_BUILD_TARGET_DIR", build_dir)
-        .env("CARGO_BUILD_TARGET", arch_str)
-        .env("CARGO_NDK_OUTPUT_PATH", cpp_lib_dest)
-        .spawn()
-        .expect("Failed to spawn cargo build")
-        .wait()
-        .expect("cargo build errored");
-    if !status.success() {
-        anyhow::bail!("cargo ndk build failed for {arch_str}");
-    }
-
-    let folder = match arch {
-        AndroidArch::X8664Linux => ARCH_X86_64,
-        AndroidArch::I686Linux => ARCH_I686,
-        AndroidArch::Armv7LinuxAbi => ARCH_ARM_V7_ABI,
-        AndroidArch::Aarch64Linux => ARCH_ARM_64_V8,
-    };
-
-    let out_lib_path = build_dir.join(arch_str).join(mode.as_str()).join(lib_name);
-    let out_lib_dest = bindings_out.join(format!("jniLibs/{folder}/{lib_name}"));
+    command::run(&mut ndk_build_command(
+        arch,
+        build_dir,
+        bindings_out,
+        mode,
+        params,
+    ))
+    .context(format!("cargo ndk build failed for {arch_str}"))?;
+
+    let out_lib_path = arch_target_dir(build_dir, arch_str, params.jobs)
+        .join(arch_str)
+        .join(mode.as_str())
+        .join(lib_name);
+    let out_lib_dest = bindings_out.join(format!("jniLibs/{}/{lib_name}", abi_folder(arch)));
 
     let parent_dir = out_lib_dest.parent().context(format!(
         "Failed to get parent directory for {}",
@@ -180,40 +366,226 @@ fn build_for_arch(
     fs::create_dir_all(parent_dir).context("Failed to create jniLibs directory")?;
     fs::copy(&out_lib_path, &out_lib_dest).context("Failed to copy file")?;
 
+    if params.split_debug_info {
+        split_debug_info(arch, &out_lib_dest, bindings_out, lib_name)
+            .with_context(|| format!("Failed to split the debug info of {arch_str}"))?;
+    }
+
     Ok(out_lib_path)
 }
 
-/// Apply caller-supplied env vars and `RUSTFLAGS` additions to a `cargo ndk` command.
-fn apply_arch_config(build_cmd: &mut Command, config: Option<&ArchBuildConfig>) {
-    let Some(config) = config else {
-        return;
+/// Move the debug info of the shipped `lib` to its `.debug` file, linked back
+/// with a `.gnu_debuglink` section. The lib in the target dir, which uniffi
+/// reads, keeps its symbols.
+fn split_debug_info(
+    arch: AndroidArch,
+    lib: &Path,
+    bindings_out: &Path,
+    lib_name: &str,
+) -> anyhow::Result<()> {
+    let debug_file = debug_file_path(bindings_out, arch, lib_name);
+    if let Some(parent) = debug_file.parent() {
+        fs::create_dir_all(parent).context("Failed to create symbols directory")?;
+    }
+    command::run(&mut only_keep_debug_command(lib, &debug_file))?;
+    command::run(&mut strip_command(lib, &debug_file))?;
+    Ok(())
+}
+
+fn debug_file_path(bindings_dir: &Path, arch: AndroidArch, lib_name: &str) -> PathBuf {
+    bindings_dir
+        .join(ANDROID_SYMBOLS_DIR)
+        .join(abi_folder(arch))
+        .join(format!("{lib_name}.debug"))
+}
+
+fn only_keep_debug_command(lib: &Path, debug_file: &Path) -> Command {
+    let mut cmd = Command::new(llvm_objcopy());
+    cmd.arg("--only-keep-debug").arg(lib).arg(debug_file);
+    cmd
+}
+
+fn strip_command(lib: &Path, debug_file: &Path) -> Command {
+    let mut debuglink = std::ffi::OsString::from("--add-gnu-debuglink=");
+    debuglink.push(debug_file);
+    let mut cmd = Command::new(llvm_objcopy());
+    cmd.arg("--strip-all").arg(debuglink).arg(lib);
+    cmd
+}
+
+/// The NDK's `llvm-objcopy`, or the one on `PATH` when no NDK is set.
+fn llvm_objcopy() -> PathBuf {
+    let host = if cfg!(target_os = "macos") {
+        "darwin-x86_64"
+    } else if cfg!(target_os = "windows") {
+        "windows-x86_64"
+    } else {
+        "linux-x86_64"
     };
-    for (key, value) in &config.extra_env {
-        build_cmd.env(key, value);
+    ["ANDROID_NDK_HOME", "ANDROID_NDK"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .map(|ndk| {
+            PathBuf::from(ndk)
+                .join("toolchains/llvm/prebuilt")
+                .join(host)
+                .join("bin/llvm-objcopy")
+        })
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("llvm-objcopy"))
+}
+
+/// Linker flag aligning the `LOAD` segments to `page_size`.
+pub fn max_page_size_flag(page_size: u64) -> String {
+    format!("-Wl,-z,max-page-size={page_size}")
+}
+
+/// Fail if a `LOAD` segment of a library in `jni_libs`, including the
+/// `libc++_shared.so` of the NDK, is aligned below `page_size`.
+fn check_page_alignment(jni_libs: &Path, page_size: u64) -> anyhow::Result<()> {
+    let mut misaligned = Vec::new();
+    for abi_dir in fs::read_dir(jni_libs)? {
+        let abi_dir = abi_dir?.path();
+        if !abi_dir.is_dir() {
+            continue;
+        }
+        for lib in fs::read_dir(abi_dir)? {
+            let lib = lib?.path();
+            if lib.extension().is_none_or(|ext| ext != "so") {
+                continue;
+            }
+            let data = fs::read(&lib)?;
+            let alignments = load_segment_alignments(&data).with_context(|| {
+                format!("Failed to read the program headers of {}", lib.display())
+            })?;
+            if let Some(align) = alignments.into_iter().find(|align| *align < page_size) {
+                let relative = lib.strip_prefix(jni_libs).unwrap_or(&lib);
+                misaligned.push(format!("{} ({align} bytes)", relative.display()));
+            }
+        }
     }
-    if !config.extra_rustflags.is_empty() {
-        let extra = config.extra_rustflags.join(" ");
-        // Preserve any RUSTFLAGS the caller already set in the environment.
-        let rustflags = match std::env::var("RUSTFLAGS") {
-            Ok(existing) if !existing.trim().is_empty() => format!("{existing} {extra}"),
-            _ => extra,
-        };
-        build_cmd.env("RUSTFLAGS", rustflags);
+    if !misaligned.is_empty() {
+        anyhow::bail!(
+            "LOAD segments aligned below the {page_size}-byte page size: {}. \
+             Check that a custom linker honors `{}`, and use NDK r27 or newer for an \
+             aligned `libc++_shared.so`.",
+            misaligned.join(", "),
+            max_page_size_flag(page_size)
+        );
+    }
+    Ok(())
+}
+
+fn load_segment_alignments(data: &[u8]) -> anyhow::Result<Vec<u64>> {
+    match FileKind::parse(data)? {
+        FileKind::Elf32 => elf_load_alignments::<FileHeader32<Endianness>>(data),
+        FileKind::Elf64 => elf_load_alignments::<FileHeader64<Endianness>>(data),
+        kind => anyhow::bail!("Not an ELF file: {kind:?}"),
+    }
+}
+
+fn elf_load_alignments<Elf: FileHeader<Endian = Endianness>>(
+    data: &[u8],
+) -> anyhow::Result<Vec<u64>> {
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    Ok(header
+        .program_headers(endian, data)?
+        .iter()
+        .filter(|segment| segment.p_type(endian) == PT_LOAD)
+        .map(|segment| segment.p_align(endian).into())
+        .collect())
+}
+
+fn abi_folder(arch: AndroidArch) -> &'static str {
+    match arch {
+        AndroidArch::X8664Linux => ARCH_X86_64,
+        AndroidArch::I686Linux => ARCH_I686,
+        AndroidArch::Armv7LinuxAbi => ARCH_ARM_V7_ABI,
+        AndroidArch::Aarch64Linux => ARCH_ARM_64_V8,
     }
 }
 
+fn ndk_build_command(
+    arch: AndroidArch,
+    build_dir: &Path,
+    bindings_out: &Path,
+    mode: &Mode,
+    params: &AndroidBindingsParams,
+) -> Command {
+    let arch_str = arch.as_str();
+    let cpp_lib_dest = bindings_out.join("jniLibs");
+
+    let mut build_cmd = Command::new("cargo");
+    build_cmd.arg("ndk").arg("-t").arg(arch_str);
+    if let Some(min_sdk) = params.min_sdk_version {
+        build_cmd.arg("--platform").arg(min_sdk.to_string());
+    }
+    build_cmd
+        .arg("build")
+        .arg("--link-libcxx-shared")
+        .arg("--lib")
+        .args(mode.cargo_args());
+
+    apply_arch_override(&mut build_cmd, &params.arch_overrides, arch_str);
+    if let Some(page_size) = params.page_size {
+        ArchBuildConfig {
+            extra_rustflags: vec![format!("-Clink-arg={}", max_page_size_flag(page_size))],
+            ..Default::default()
+        }
+        .apply_rustflags(&mut build_cmd);
+    }
+
+    build_cmd
+        .env(
+            "CARGO_BUILD_TARGET_DIR",
+            arch_target_dir(build_dir, arch_str, params.jobs),
+        )
+        .env("CARGO_BUILD_TARGET", arch_str)
+        .env("CARGO_NDK_OUTPUT_PATH", cpp_lib_dest);
+    build_cmd
+}
+
 /// Build `arch` with the plain NDK linker only to extract uniffi metadata: a
 /// custom linker (e.g. Zig) can drop the `.symtab` uniffi-bindgen reads, which the
 /// NDK linker keeps. This lib may not run, but bindgen never runs it; a separate
 /// target dir keeps the shipped jniLibs untouched. Caller env (e.g. an overriding
-/// lib dir) is applied, but `extra_rustflags` is not — this build must use the NDK
-/// linker.
+/// lib dir) and features are applied, but `extra_rustflags` is not — this build
+/// must use the NDK linker.
 fn build_bindgen_lib(
     arch: AndroidArch,
     lib_name: &str,
     build_dir: &Path,
     params: &AndroidBindingsParams,
 ) -> anyhow::Result<PathBuf> {
+    let arch_str = arch.as_str();
+    command::run(&mut bindgen_lib_command(arch, build_dir, params)).context(format!(
+        "cargo ndk build (bindgen lib) failed for {arch_str}"
+    ))?;
+
+    let out_lib_path = bindgen_lib_path(arch, lib_name, build_dir);
+    if !out_lib_path.exists() {
+        anyhow::bail!(
+            "NDK bindgen lib missing at {} (needed for uniffi metadata)",
+            out_lib_path.display()
+        );
+    }
+    Ok(out_lib_path)
+}
+
+fn bindgen_lib_path(arch: AndroidArch, lib_name: &str, build_dir: &Path) -> PathBuf {
+    build_dir
+        .join("bindgen")
+        .join(arch.as_str())
+        .join("debug")
+        .join(lib_name)
+}
+
+fn bindgen_lib_command(
+    arch: AndroidArch,
+    build_dir: &Path,
+    params: &AndroidBindingsParams,
+) -> Command {
     let arch_str = arch.as_str();
     let bindgen_target = build_dir.join("bindgen");
 
@@ -228,41 +600,181 @@ fn build_bindgen_lib(
         .arg("--lib");
 
     if let Some(config) = params.arch_overrides.get(arch_str) {
-        for (key, value) in &config.extra_env {
-            build_cmd.env(key, value);
-        }
+        config.apply_features(&mut build_cmd);
+        config.apply_env(&mut build_cmd);
     }
 
-    let status = build_cmd
+    build_cmd
         .env("CARGO_BUILD_TARGET_DIR", &bindgen_target)
         .env("CARGO_BUILD_TARGET", arch_str)
-        .env("CARGO_NDK_OUTPUT_PATH", bindgen_target.join("jniLibs"))
-        .spawn()
-        .expect("Failed to spawn cargo build for bindgen lib")
-        .wait()
-        .expect("cargo build (bindgen lib) errored");
-    if !status.success() {
-        anyhow::bail!("cargo ndk build (bindgen lib) failed for {arch_str}");
-    }
-
-    let out_lib_path = bindgen_target.join(arch_str).join("debug").join(lib_name);
-    if !out_lib_path.exists() {
-        anyhow::bail!(
-            "NDK bindgen lib missing at {} (needed for uniffi metadata)",
-            out_lib_path.display()
-        );
-    }
-    Ok(out_lib_path)
+        .env("CARGO_NDK_OUTPUT_PATH", bindgen_target.join("jniLibs"));
+    build_cmd
 }
 
-fn move_bindings(bindings_out: &Path, bindings_dest: &Path) {
-    if let Ok(info) = fs::metadata(bindings_dest) {
-        if !info.is_dir() {
-            panic!("bindings directory exists and is not a directory");
+/// Make the bindings directory a self-contained Android library module that
+/// compiles the generated Kotlin with the `jniLibs`, so it can be added with
+/// `includeBuild(...)` or published to a Maven repository:
+///
+/// ```text
+/// MoproAndroidBindings/
+/// ├── settings.gradle.kts
+/// ├── build.gradle.kts
+/// ├── AndroidManifest.xml
+/// ├── gradlew, gradlew.bat, gradle/wrapper/
+/// ├── jniLibs/<abi>/lib<name>.so
+/// └── uniffi/mopro/mopro.kt
+/// ```
+fn write_gradle_module(
+    project_dir: &Path,
+    bindings_out: &Path,
+    params: &AndroidBindingsParams,
+) -> anyhow::Result<()> {
+    let artifact_id = raw_project_name_from_toml(project_dir)?;
+    let version = version_from_toml(project_dir)?;
+    let min_sdk = params.min_sdk_version.unwrap_or(24);
+    let namespace = format!("{ANDROID_UNIFFI_DIR}.{}", params.names.package_name);
+    let AndroidGradleVersions {
+        agp,
+        kotlin,
+        compile_sdk,
+    } = &params.gradle;
+
+    let settings = format!(
+        r#"pluginManagement {{
+    repositories {{
+        google()
+        mavenCentral()
+        gradlePluginPortal()
+    }}
+}}
+dependencyResolutionManagement {{
+    repositories {{
+        google()
+        mavenCentral()
+    }}
+}}
+
+rootProject.name = "{artifact_id}"
+"#
+    );
+    let build = format!(
+        r#"plugins {{
+    id("com.android.library") version "{agp}"
+    id("org.jetbrains.kotlin.android") version "{kotlin}"
+    `maven-publish`
+}}
+
+group = "{ANDROID_MAVEN_GROUP}"
+version = "{version}"
+
+android {{
+    namespace = "{namespace}"
+    compileSdk = {compile_sdk}
+
+    defaultConfig {{
+        minSdk = {min_sdk}
+    }}
+
+    sourceSets {{
+        getByName("main") {{
+            manifest.srcFile("AndroidManifest.xml")
+            java.srcDirs("{ANDROID_UNIFFI_DIR}")
+            jniLibs.srcDirs("{ANDROID_JNILIBS_DIR}")
+        }}
+    }}
+
+    compileOptions {{
+        sourceCompatibility = JavaVersion.VERSION_1_8
+        targetCompatibility = JavaVersion.VERSION_1_8
+    }}
+    kotlinOptions {{
+        jvmTarget = "1.8"
+    }}
+
+    publishing {{
+        singleVariant("release") {{
+            withSourcesJar()
+        }}
+    }}
+}}
+
+dependencies {{
+    // Uniffi
+    implementation("net.java.dev.jna:jna:5.13.0@aar")
+    implementation("org.jetbrains.kotlinx:kotlinx-coroutines-core:1.6.4")
+}}
+
+publishing {{
+    publications {{
+        register<MavenPublication>("release") {{
+            groupId = "{ANDROID_MAVEN_GROUP}"
+            artifactId = "{artifact_id}"
+            version = "{version}"
+            pom {{
+                name.set("{artifact_id}")
+                description.set("Mopro Android bindings for {artifact_id}")
+            }}
+            afterEvaluate {{
+                from(components["release"])
+            }}
+        }}
+    }}
+    repositories {{
+        maven {{
+            name = "bindings"
+            url = uri(layout.projectDirectory.dir("{ANDROID_MAVEN_DIR}"))
+        }}
+    }}
+}}
+"#
+    );
+    let manifest = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" />
+"#;
+
+    fs::write(bindings_out.join("settings.gradle.kts"), settings)
+        .context("Failed to write settings.gradle.kts")?;
+    fs::write(bindings_out.join("build.gradle.kts"), build)
+        .context("Failed to write build.gradle.kts")?;
+    fs::write(bindings_out.join("AndroidManifest.xml"), manifest)
+        .context("Failed to write AndroidManifest.xml")?;
+
+    for (name, contents) in GRADLE_WRAPPER_FILES {
+        let path = bindings_out.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create Gradle wrapper directory")?;
         }
-        fs::remove_dir_all(bindings_dest).expect("Failed to remove bindings directory");
+        fs::write(&path, contents).with_context(|| format!("Failed to write {name}"))?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(
+            bindings_out.join("gradlew"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .context("Failed to make gradlew executable")?;
     }
-    fs::rename(bindings_out, bindings_dest).expect("Failed to move bindings into place");
+    Ok(())
+}
+
+/// Build the release `.aar` and publish it with its POM to `maven/`.
+fn publish_aar(module_dir: &Path) -> anyhow::Result<()> {
+    command::run(&mut publish_aar_command(module_dir))
+        .context("Failed to publish the Android bindings AAR")?;
+    Ok(())
+}
+
+#[cfg(windows)]
+const GRADLE_WRAPPER: &str = "gradlew.bat";
+#[cfg(not(windows))]
+const GRADLE_WRAPPER: &str = "gradlew";
+
+fn publish_aar_command(module_dir: &Path) -> Command {
+    let mut cmd = Command::new(module_dir.join(GRADLE_WRAPPER));
+    cmd.arg("publishReleasePublicationToBindingsRepository")
+        .current_dir(module_dir);
+    cmd
 }
 
 fn generate_android_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::Result<()> {
@@ -271,7 +783,7 @@ fn generate_android_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::R
         .parent()
         .context("Failed to get parent directory")?;
     let config_path = parent_dir.join("uniffi_config.toml");
-    fs::write(&config_path, content).expect("Failed to write uniffi_config.toml");
+    fs::write(&config_path, content).context("Failed to write uniffi_config.toml")?;
 
     generate(GenerateOptions {
         languages: vec![TargetLanguage::Kotlin],
@@ -291,11 +803,13 @@ fn generate_android_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::R
     Ok(())
 }
 
-fn reformat_kotlin_package(
+/// Move the generated Kotlin file from `uniffi/<gen module>/` to
+/// `uniffi/<out module>/` and rewrite its package declaration to match.
+pub(super) fn reformat_kotlin_package(
     gen_android_module_name: &str,
     gen_android_kt_file_name: &str,
     out_android_module_name: &str,
-    out_android_kt_file_name: &&str,
+    out_android_kt_file_name: &str,
     bindings_out: &Path,
 ) -> anyhow::Result<()> {
     let generated_kt_file = bindings_out
@@ -327,3 +841,114 @@ fn reformat_kotlin_package(
     fs::write(&out_android_kt_file, modified_content)
         .context("Failed to write modified Kotlin file")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::mktemp;
+    use object::elf::{PT_DYNAMIC, PT_LOAD};
+
+    /// A little-endian ELF64 shared object holding only the program headers
+    /// `(p_type, p_align)`.
+    fn elf64(segments: &[(u32, u64)]) -> Vec<u8> {
+        let mut data = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        data.extend(3u16.to_le_bytes()); // e_type: ET_DYN
+        data.extend(183u16.to_le_bytes()); // e_machine: EM_AARCH64
+        data.extend(1u32.to_le_bytes()); // e_version
+        data.extend(0u64.to_le_bytes()); // e_entry
+        data.extend(64u64.to_le_bytes()); // e_phoff
+        data.extend(0u64.to_le_bytes()); // e_shoff
+        data.extend(0u32.to_le_bytes()); // e_flags
+        data.extend(64u16.to_le_bytes()); // e_ehsize
+        data.extend(56u16.to_le_bytes()); // e_phentsize
+        data.extend((segments.len() as u16).to_le_bytes()); // e_phnum
+        data.extend([0; 6]); // e_shentsize, e_shnum, e_shstrndx
+        for &(p_type, p_align) in segments {
+            data.extend(p_type.to_le_bytes());
+            data.extend(5u32.to_le_bytes()); // p_flags: R + X
+            data.extend([0; 40]); // p_offset, p_vaddr, p_paddr, p_filesz, p_memsz
+            data.extend(p_align.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_load_segment_alignments() {
+        let data = elf64(&[(PT_LOAD, 0x4000), (PT_DYNAMIC, 8), (PT_LOAD, 0x1000)]);
+        assert_eq!(
+            load_segment_alignments(&data).unwrap(),
+            vec![0x4000, 0x1000]
+        );
+        assert!(load_segment_alignments(b"not an elf file").is_err());
+    }
+
+    #[test]
+    fn test_check_page_alignment() {
+        let jni_libs = mktemp().unwrap();
+        fs::create_dir_all(jni_libs.join("arm64-v8a")).unwrap();
+        fs::create_dir_all(jni_libs.join("x86_64")).unwrap();
+        let aligned_16k = elf64(&[(PT_LOAD, 0x4000), (PT_LOAD, 0x4000), (PT_DYNAMIC, 8)]);
+        let aligned_4k = elf64(&[(PT_LOAD, 0x4000), (PT_LOAD, 0x1000)]);
+        fs::write(jni_libs.join("arm64-v8a/libmopro.so"), &aligned_16k).unwrap();
+        fs::write(jni_libs.join("x86_64/libmopro.so"), &aligned_16k).unwrap();
+        fs::write(jni_libs.join("x86_64/libc++_shared.so"), &aligned_4k).unwrap();
+        // Only `.so` files are checked
+        fs::write(jni_libs.join("x86_64/libmopro.so.debug"), &aligned_4k).unwrap();
+
+        check_page_alignment(&jni_libs, 4096).unwrap();
+        let error = check_page_alignment(&jni_libs, 16384)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with(
+                "LOAD segments aligned below the 16384-byte page size: \
+                 x86_64/libc++_shared.so (4096 bytes)."
+            ),
+            "{error}"
+        );
+        assert!(error.contains("-Wl,-z,max-page-size=16384"), "{error}");
+        assert!(!error.contains("libmopro"), "{error}");
+
+        fs::remove_dir_all(jni_libs).unwrap();
+    }
+
+    #[test]
+    fn test_write_gradle_module() {
+        let project_dir = mktemp().unwrap();
+        let bindings_out = project_dir.join(ANDROID_BINDINGS_DIR);
+        fs::create_dir(&bindings_out).unwrap();
+        fs::write(
+            project_dir.join("Cargo.toml"),
+            "[package]\nname = \"my-circuit\"\nversion = \"0.2.0\"\n",
+        )
+        .unwrap();
+        let params = AndroidBindingsParams {
+            gradle: AndroidGradleVersions {
+                agp: "8.7.3".to_string(),
+                kotlin: "2.1.0".to_string(),
+                compile_sdk: 35,
+            },
+            ..Default::default()
+        };
+
+        write_gradle_module(&project_dir, &bindings_out, &params).unwrap();
+        let build = fs::read_to_string(bindings_out.join("build.gradle.kts")).unwrap();
+        assert!(build.contains(r#"id("com.android.library") version "8.7.3""#));
+        assert!(build.contains(r#"id("org.jetbrains.kotlin.android") version "2.1.0""#));
+        assert!(build.contains("compileSdk = 35"));
+        for (name, contents) in GRADLE_WRAPPER_FILES {
+            assert_eq!(fs::read(bindings_out.join(name)).unwrap(), contents);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(bindings_out.join("gradlew"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::Context;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::constants::{
+    Arch, CArch, CPlatform, Mode, C_BINDINGS_DIR, C_HEADER_FILE, C_INCLUDE_DIR, C_LIB_DIR,
+};
+use super::plan::BuildPlan;
+use super::version_from_toml;
+use super::ArchOverrides;
+use super::{apply_arch_override, cargo_build_lib_command, install_arch_command};
+use super::{
+    cleanup_tmp_local, command, create_staging_dir, install_arch, staging_dir, PlatformBuilder,
+};
+use super::{move_bindings_into_place, project_name_from_toml, raw_project_name_from_toml};
+
+/// Generator of the C header, run over the crate's `extern "C"` functions.
+pub const CBINDGEN: &str = "cbindgen";
+
+pub struct CBindingsParams {
+    /// Directory under the project the header and libraries are written to.
+    pub bindings_dir: String,
+    /// File name of the header under `include/`.
+    pub header_file: String,
+    /// Per-target-triple overrides, keyed by [`CArch::as_str`].
+    pub arch_overrides: ArchOverrides,
+}
+
+impl Default for CBindingsParams {
+    fn default() -> Self {
+        Self {
+            bindings_dir: C_BINDINGS_DIR.to_string(),
+            header_file: C_HEADER_FILE.to_string(),
+            arch_overrides: ArchOverrides::default(),
+        }
+    }
+}
+
+impl PlatformBuilder for CPlatform {
+    type Arch = CArch;
+    type Params = CBindingsParams;
+
+    /// Build the `staticlib` and `cdylib` for each Linux target and ship them
+    /// with a C header and a pkg-config file per target:
+    ///
+    /// ```text
+    /// MoproCBindings/
+    /// ├── include/<header_file>
+    /// └── lib/<target>/
+    ///     ├── lib<name>.a
+    ///     ├── lib<name>.so
+    ///     └── pkgconfig/<name>.pc
+    /// ```
+    ///
+    /// The header is generated by [`CBINDGEN`] and declares the crate's
+    /// `#[no_mangle] pub extern "C"` functions; the UniFFI exports are not
+    /// part of it. `Libs.private` lists the system libraries rustc reports
+    /// for the static library of each target.
+    fn build(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: Vec<Self::Arch>,
+        params: Self::Params,
+    ) -> anyhow::Result<PathBuf> {
+        check_cbindgen()?;
+        let uniffi_style_identifier = project_name_from_toml(project_dir)
+            .context("Failed to get project name from Cargo.toml")?;
+        let crate_name = raw_project_name_from_toml(project_dir)
+            .context("Failed to get package name from Cargo.toml")?;
+
+        let static_lib_name = format!("lib{uniffi_style_identifier}.a");
+        let dynamic_lib_name = format!("lib{uniffi_style_identifier}.so");
+
+        // Paths for the generated files
+        let build_dir = project_dir.join("build");
+        let work_dir = create_staging_dir(&build_dir, &params.bindings_dir)?;
+        let bindings_out = work_dir.join(&params.bindings_dir);
+        let include_out = bindings_out.join(C_INCLUDE_DIR);
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        fs::create_dir_all(&include_out).context("Failed to create include directory")?;
+
+        let version = version_from_toml(project_dir)?;
+        for arch in target_archs {
+            let out_dir = build_for_arch(arch, &build_dir, &mode, &params.arch_overrides).context(
+                format!("Failed to build for architecture: {}", arch.as_str()),
+            )?;
+            let native_libs = native_static_libs(arch, &build_dir, &mode, &params.arch_overrides)?;
+
+            let lib_dest = bindings_out.join(C_LIB_DIR).join(arch.as_str());
+            fs::create_dir_all(lib_dest.join("pkgconfig"))
+                .context("Failed to create lib directory")?;
+            for lib_name in [&static_lib_name, &dynamic_lib_name] {
+                fs::copy(out_dir.join(lib_name), lib_dest.join(lib_name))
+                    .context(format!("Failed to copy {lib_name}"))?;
+            }
+            fs::write(
+                lib_dest
+                    .join("pkgconfig")
+                    .join(format!("{uniffi_style_identifier}.pc")),
+                pkg_config(&uniffi_style_identifier, &version, arch, &native_libs),
+            )
+            .context("Failed to write pkg-config file")?;
+        }
+
+        command::run(&mut cbindgen_command(
+            project_dir,
+            &crate_name,
+            &include_out.join(&params.header_file),
+        ))
+        .context("Failed to generate the C header")?;
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir)?;
+
+        Ok(bindings_dest)
+    }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let crate_name = raw_project_name_from_toml(project_dir)?;
+        let static_lib_name = format!("lib{uniffi_style_identifier}.a");
+        let dynamic_lib_name = format!("lib{uniffi_style_identifier}.so");
+        let build_dir = project_dir.join("build");
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        let bindings_out = staging_dir(&build_dir, &params.bindings_dir).join(&params.bindings_dir);
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        for arch in target_archs {
+            let arch_str = arch.as_str();
+            let out_dir = build_dir.join(arch_str).join(mode.as_str());
+            let lib_dest = bindings_out.join(C_LIB_DIR).join(arch_str);
+            plan.command(
+                format!("Install the {arch_str} target"),
+                &install_arch_command(arch_str),
+                vec![],
+                vec![],
+            );
+            plan.command(
+                format!("Build for {arch_str}"),
+                &cargo_build_lib_command(arch_str, &build_dir, &mode, &params.arch_overrides),
+                vec![project_dir.to_path_buf()],
+                vec![
+                    out_dir.join(&static_lib_name),
+                    out_dir.join(&dynamic_lib_name),
+                ],
+            );
+            plan.command(
+                format!("Read the system libraries the {arch_str} static library needs"),
+                &native_static_libs_command(arch_str, &build_dir, &mode, &params.arch_overrides),
+                vec![project_dir.to_path_buf()],
+                vec![],
+            );
+            plan.step(
+                format!("Copy the {arch_str} libraries and write their pkg-config file"),
+                vec![
+                    out_dir.join(&static_lib_name),
+                    out_dir.join(&dynamic_lib_name),
+                ],
+                vec![
+                    lib_dest.join(&static_lib_name),
+                    lib_dest.join(&dynamic_lib_name),
+                    lib_dest
+                        .join("pkgconfig")
+                        .join(format!("{uniffi_style_identifier}.pc")),
+                ],
+            );
+        }
+        let header = bindings_out.join(C_INCLUDE_DIR).join(&params.header_file);
+        plan.command(
+            "Generate the C header",
+            &cbindgen_command(project_dir, &crate_name, &header),
+            vec![project_dir.to_path_buf()],
+            vec![header.clone()],
+        );
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
+
+/// Build `arch` and return the directory holding its libraries.
+fn build_for_arch(
+    arch: CArch,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> anyhow::Result<PathBuf> {
+    let arch_str = arch.as_str();
+    install_arch(arch_str.to_string())?;
+
+    command::run(&mut cargo_build_lib_command(
+        arch_str, build_dir, mode, overrides,
+    ))
+    .context(format!("cargo build failed for {arch_str}"))?;
+
+    Ok(build_dir.join(arch_str).join(mode.as_str()))
+}
+
+/// `cargo rustc` run of the static library that makes rustc print the system
+/// libraries it links against, with the same settings as the build.
+fn native_static_libs_command(
+    target: &str,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("rustc")
+        .arg("--lib")
+        .args(["--crate-type", "staticlib"])
+        .args(mode.cargo_args());
+    apply_arch_override(&mut cmd, overrides, target);
+    cmd.env("CARGO_BUILD_TARGET_DIR", build_dir)
+        .env("CARGO_BUILD_TARGET", target)
+        .args(["--", "--print", "native-static-libs"]);
+    cmd
+}
+
+/// Linker flags for the system libraries the static library of `arch` needs.
+/// Cargo replays the note of rustc when the library is already built.
+fn native_static_libs(
+    arch: CArch,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> anyhow::Result<String> {
+    let arch_str = arch.as_str();
+    let output = command::run_captured(&mut native_static_libs_command(
+        arch_str, build_dir, mode, overrides,
+    ))
+    .context(format!(
+        "Failed to read the native static libraries for {arch_str}"
+    ))?;
+    parse_native_static_libs(&String::from_utf8_lossy(&output.stderr))
+        .map(str::to_string)
+        .context(format!(
+            "rustc did not print the native static libraries for {arch_str}"
+        ))
+}
+
+/// Flags of the `note: native-static-libs: ...` line rustc prints.
+fn parse_native_static_libs(stderr: &str) -> Option<&str> {
+    stderr.lines().find_map(|line| {
+        line.split_once("native-static-libs:")
+            .map(|(_, libs)| libs.trim())
+    })
+}
+
+/// Fail with install instructions unless [`CBINDGEN`] is available. Nothing
+/// is installed.
+fn check_cbindgen() -> anyhow::Result<()> {
+    if Command::new(CBINDGEN).arg("--version").output().is_err() {
+        anyhow::bail!(
+            "{CBINDGEN} is required to generate the C header. Install it with:\n\n    \
+            cargo install {CBINDGEN} --locked"
+        );
+    }
+    Ok(())
+}
+
+fn cbindgen_command(project_dir: &Path, crate_name: &str, header: &Path) -> Command {
+    let mut cmd = Command::new(CBINDGEN);
+    cmd.args(["--lang", "c", "--crate", crate_name])
+        .arg("--output")
+        .arg(header)
+        .arg(project_dir);
+    cmd
+}
+
+/// pkg-config file for `lib/<target>/pkgconfig/`, relative to its own
+/// location so the bindings directory can be moved anywhere.
+fn pkg_config(lib_name: &str, version: &str, arch: CArch, native_libs: &str) -> String {
+    format!(
+        r#"prefix=${{pcfiledir}}/../../..
+includedir=${{prefix}}/{C_INCLUDE_DIR}
+libdir=${{prefix}}/{C_LIB_DIR}/{target}
+
+Name: {lib_name}
+Description: Mopro bindings for {lib_name}
+Version: {version}
+Cflags: -I${{includedir}}
+Libs: -L${{libdir}} -l{lib_name}
+Libs.private: {native_libs}
+"#,
+        target = arch.as_str()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_native_static_libs() {
+        let stderr = "   Compiling my-circuit v0.1.0\n\
+            note: Link against the following native artifacts when linking against this static library. \
+            The order and any duplication can be significant on some platforms.\n\
+            \n\
+            note: native-static-libs: -lgcc_s -lutil -lrt -lpthread -lm -ldl -lc\n\
+            \n    Finished `release` profile [optimized] target(s) in 0.50s\n";
+        assert_eq!(
+            parse_native_static_libs(stderr),
+            Some("-lgcc_s -lutil -lrt -lpthread -lm -ldl -lc")
+        );
+        assert_eq!(
+            parse_native_static_libs("    Finished `dev` profile\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Runs the external tools (`cargo`, `lipo`, `xcodebuild`, `wasm-pack`, ...)
+//! the platform builders shell out to.
+//!
+//! Every command is logged before it starts, its output is streamed to the
+//! terminal as usual and kept, and a non-zero exit status becomes an `Err`
+//! carrying the tail of that output instead of a panic or `exit`.
+
+use anyhow::Context;
+use std::io::{Read, Write};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::constants::COMMAND_TIMEOUT_ENV;
+use super::plan::PlannedCommand;
+
+/// Number of trailing output lines kept in the error of a failed command.
+const ERROR_OUTPUT_LINES: usize = 40;
+
+/// How often a running command is polled for its exit status.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Run `cmd`, echoing its output, and fail unless it exits successfully.
+///
+/// The command is killed after `MOPRO_COMMAND_TIMEOUT` seconds when the
+/// variable is set.
+pub fn run(cmd: &mut Command) -> anyhow::Result<Output> {
+    run_with(cmd, true, timeout_from_env()?)
+}
+
+/// Like [`run`], without echoing the output, for commands whose output is
+/// parsed by the caller.
+pub fn run_captured(cmd: &mut Command) -> anyhow::Result<Output> {
+    run_with(cmd, false, timeout_from_env()?)
+}
+
+fn run_with(cmd: &mut Command, echo: bool, timeout: Option<Duration>) -> anyhow::Result<Output> {
+    let command_line = PlannedCommand::from(&*cmd).to_string();
+    match cmd.get_current_dir() {
+        Some(dir) => println!("Running `{command_line}` in {}", dir.display()),
+        None => println!("Running `{command_line}`"),
+    }
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "Failed to spawn `{}`, is it installed?",
+                cmd.get_program().to_string_lossy()
+            )
+        })?;
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let captured_stdout = Arc::new(Mutex::new(Vec::new()));
+    let captured_stderr = Arc::new(Mutex::new(Vec::new()));
+    let stdout_reader = {
+        let captured = captured_stdout.clone();
+        thread::spawn(move || tee(stdout, echo.then(std::io::stdout), &captured))
+    };
+    let stderr_reader = {
+        let captured = captured_stderr.clone();
+        thread::spawn(move || tee(stderr, echo.then(std::io::stderr), &captured))
+    };
+
+    let started = Instant::now();
+    let status: Option<ExitStatus> = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+            // The process may exit between the poll and the kill.
+            let _ = child.kill();
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // After a timeout, processes spawned by the killed command may still hold
+    // the pipes open, so only wait for the readers when it exited by itself.
+    if status.is_some() {
+        let _ = stdout_reader.join();
+        let _ = stderr_reader.join();
+    }
+    let output = Output {
+        status: status.unwrap_or_default(),
+        stdout: captured_stdout.lock().unwrap().clone(),
+        stderr: captured_stderr.lock().unwrap().clone(),
+    };
+
+    match status {
+        None => anyhow::bail!(
+            "`{command_line}` timed out after {:?}{}",
+            timeout.unwrap_or_default(),
+            output_tail(&output)
+        ),
+        Some(status) if !status.success() => anyhow::bail!(
+            "`{command_line}` failed with {status}{}",
+            output_tail(&output)
+        ),
+        Some(_) => Ok(output),
+    }
+}
+
+/// Copy everything from `source` into `captured` and `sink`, if any.
+fn tee(mut source: impl Read, mut sink: Option<impl Write>, captured: &Mutex<Vec<u8>>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match source.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if let Some(sink) = sink.as_mut() {
+                    let _ = sink.write_all(&buf[..n]);
+                    let _ = sink.flush();
+                }
+                captured.lock().unwrap().extend_from_slice(&buf[..n]);
+            }
+        }
+    }
+}
+
+fn timeout_from_env() -> anyhow::Result<Option<Duration>> {
+    match std::env::var(COMMAND_TIMEOUT_ENV) {
+        Ok(secs) if !secs.is_empty() => {
+            let secs: u64 = secs.parse().with_context(|| {
+                format!("{COMMAND_TIMEOUT_ENV} must be a number of seconds, got {secs}")
+            })?;
+            Ok(Some(Duration::from_secs(secs)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn output_tail(output: &Output) -> String {
+    let mut tail = String::new();
+    for (name, stream) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        let text = String::from_utf8_lossy(stream);
+        let lines: Vec<&str> = text.lines().collect();
+        if lines.is_empty() {
+            continue;
+        }
+        let start = lines.len().saturating_sub(ERROR_OUTPUT_LINES);
+        tail.push_str(&format!("\n--- {name} ---\n{}", lines[start..].join("\n")));
+    }
+    tail
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        cmd
+    }
+
+    #[test]
+    fn test_run_captures_output() {
+        let output = run_with(&mut sh("echo out; echo err >&2"), false, None).unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn test_run_non_zero_exit_status() {
+        let error = run_with(&mut sh("echo broken >&2; exit 3"), false, None).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("failed with exit status: 3"), "{message}");
+        assert!(message.contains("--- stderr ---\nbroken"), "{message}");
+    }
+
+    #[test]
+    fn test_run_missing_program() {
+        let error = run_with(
+            &mut Command::new("mopro-command-that-does-not-exist"),
+            false,
+            None,
+        )
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to spawn `mopro-command-that-does-not-exist`, is it installed?"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_run_timeout() {
+        let error =
+            run_with(&mut sh("sleep 5"), false, Some(Duration::from_millis(100))).unwrap_err();
+        assert!(error.to_string().contains("timed out after"), "{error}");
+    }
+}
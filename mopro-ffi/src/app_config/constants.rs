@@ -5,16 +5,29 @@ pub const IOS_ARCHS_ENV: &str = "IOS_ARCHS";
 pub const ANDROID_ARCHS_ENV: &str = "ANDROID_ARCHS";
 pub const FLUTTER_ARCHS_ENV: &str = "FLUTTER_ARCHS";
 pub const REACT_NATIVE_ARCHS_ENV: &str = "REACT_NATIVE_ARCHS";
+pub const PYTHON_ARCHS_ENV: &str = "PYTHON_ARCHS";
+pub const JVM_ARCHS_ENV: &str = "JVM_ARCHS";
+pub const NODE_ARCHS_ENV: &str = "NODE_ARCHS";
+pub const C_ARCHS_ENV: &str = "C_ARCHS";
+pub const COMMAND_TIMEOUT_ENV: &str = "MOPRO_COMMAND_TIMEOUT";
 
 pub const IOS_BINDINGS_DIR: &str = "MoproiOSBindings";
 pub const IOS_SWIFT_FILE: &str = "mopro.swift";
 pub const IOS_XCFRAMEWORKS_DIR: &str = "MoproBindings.xcframework";
+pub const IOS_SWIFT_PACKAGE_FILE: &str = "Package.swift";
+pub const IOS_SWIFT_PACKAGE_DIST_DIR: &str = "dist";
 
 pub const ANDROID_BINDINGS_DIR: &str = "MoproAndroidBindings";
 pub const ANDROID_JNILIBS_DIR: &str = "jniLibs";
 pub const ANDROID_UNIFFI_DIR: &str = "uniffi";
 pub const ANDROID_PACKAGE_NAME: &str = "mopro";
 pub const ANDROID_KT_FILE: &str = "mopro.kt";
+pub const ANDROID_MAVEN_DIR: &str = "maven";
+pub const ANDROID_MAVEN_GROUP: &str = "uniffi";
+pub const ANDROID_SYMBOLS_DIR: &str = "symbols";
+pub const ANDROID_AGP_VERSION: &str = "8.5.1";
+pub const ANDROID_KOTLIN_VERSION: &str = "1.9.0";
+pub const ANDROID_COMPILE_SDK: u32 = 34;
 
 pub const WEB_BINDINGS_DIR: &str = "MoproWasmBindings";
 
@@ -27,10 +40,30 @@ pub const ARCH_ARM_64_V8: &str = "arm64-v8a";
 pub const FLUTTER_BINDINGS_DIR: &str = "mopro_flutter_bindings";
 pub const REACT_NATIVE_BINDINGS_DIR: &str = "MoproReactNativeBindings";
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub const PYTHON_BINDINGS_DIR: &str = "MoproPythonBindings";
+pub const PYTHON_PACKAGE_NAME: &str = "mopro";
+
+pub const JVM_BINDINGS_DIR: &str = "MoproJvmBindings";
+pub const JVM_RESOURCES_DIR: &str = "resources";
+pub const JVM_NATIVES_JAR: &str = "mopro-natives.jar";
+
+pub const NODE_BINDINGS_DIR: &str = "MoproNodeBindings";
+
+pub const C_BINDINGS_DIR: &str = "MoproCBindings";
+pub const C_INCLUDE_DIR: &str = "include";
+pub const C_LIB_DIR: &str = "lib";
+pub const C_HEADER_FILE: &str = "mopro.h";
+
+/// Prefix of the build modes naming a custom cargo profile, e.g. `profile:mobile`.
+pub const MODE_PROFILE_PREFIX: &str = "profile:";
+
+/// Cargo profile the bindings are built with.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
     Debug,
     Release,
+    /// A custom `[profile.<name>]` from the project's `Cargo.toml`.
+    Profile(String),
 }
 
 struct ModeInfo {
@@ -50,24 +83,49 @@ const MODES: [ModeInfo; 2] = [
 ];
 
 impl Mode {
-    pub fn as_str(&self) -> &'static str {
-        MODES
-            .iter()
-            .find(|info| info.mode == *self)
-            .map(|info| info.str)
-            .expect("Unsupported Mode, only support 'release' and 'debug'")
-    }
-
-    pub fn parse_from_str(s: &str) -> Self {
+    /// Name of the mode, which is also the subdirectory of the target dir
+    /// cargo writes its artifacts to.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Mode::Profile(name) => name,
+            mode => MODES
+                .iter()
+                .find(|info| info.mode == *mode)
+                .map(|info| info.str)
+                .expect("Built-in modes are listed in MODES"),
+        }
+    }
+
+    /// `debug` (or cargo's `dev`), `release`, or `profile:<name>` for a
+    /// custom cargo profile. Other names are rejected, so that a typo such as
+    /// `relase` is not taken for a profile.
+    pub fn parse_from_str(s: &str) -> anyhow::Result<Self> {
+        if let Some(name) = s.strip_prefix(MODE_PROFILE_PREFIX) {
+            if name.is_empty() {
+                anyhow::bail!("Missing the profile name in build mode {s}");
+            }
+            return Ok(Self::builtin(name).unwrap_or_else(|| Mode::Profile(name.to_string())));
+        }
+        Self::builtin(s).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown build mode {s}, expected one of {}, or {MODE_PROFILE_PREFIX}<name>",
+                Self::all_strings().join(", ")
+            )
+        })
+    }
+
+    fn builtin(s: &str) -> Option<Self> {
+        if s.eq_ignore_ascii_case("dev") {
+            return Some(Mode::Debug);
+        }
         MODES
             .iter()
-            .find(|info| info.str.to_lowercase() == s.to_lowercase())
-            .map(|info| info.mode)
-            .expect("Unsupported Mode String, only support 'release' and 'debug'")
+            .find(|info| info.str.eq_ignore_ascii_case(s))
+            .map(|info| info.mode.clone())
     }
 
     pub fn from_idx(idx: usize) -> Self {
-        MODES[idx].mode
+        MODES[idx].mode.clone()
     }
 
     pub fn idx(s: &str) -> Option<usize> {
@@ -78,9 +136,19 @@ impl Mode {
             .map(|(i, _)| i)
     }
 
+    /// Names of the built-in modes.
     pub fn all_strings() -> Vec<&'static str> {
         MODES.iter().map(|info| info.str).collect()
     }
+
+    /// Arguments selecting this profile for `cargo build`.
+    pub fn cargo_args(&self) -> Vec<&str> {
+        match self {
+            Mode::Debug => vec![],
+            Mode::Release => vec!["--release"],
+            Mode::Profile(name) => vec!["--profile", name],
+        }
+    }
 }
 
 //
@@ -461,6 +529,215 @@ impl Arch for ReactNativeArch {
     }
 }
 
+/// Desktop targets of the platforms that build for the machine running them
+/// (Python, JVM and Node).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostArch {
+    X8664Linux,
+    Aarch64Linux,
+    X8664Darwin,
+    Aarch64Darwin,
+}
+
+struct HostArchInfo {
+    arch: HostArch,
+    str: &'static str,
+    description: &'static str,
+    /// `std::env::consts::ARCH` and `OS` of a machine of this target
+    host: (&'static str, &'static str),
+}
+
+/// Ordered as the [`HostArch`] variants.
+const HOST_ARCHS: [HostArchInfo; 4] = [
+    HostArchInfo {
+        arch: HostArch::X8664Linux,
+        str: "x86_64-unknown-linux-gnu",
+        description: "64-bit Linux (x86_64 architecture)",
+        host: ("x86_64", "linux"),
+    },
+    HostArchInfo {
+        arch: HostArch::Aarch64Linux,
+        str: "aarch64-unknown-linux-gnu",
+        description: "64-bit Linux (ARM64 architecture)",
+        host: ("aarch64", "linux"),
+    },
+    HostArchInfo {
+        arch: HostArch::X8664Darwin,
+        str: "x86_64-apple-darwin",
+        description: "macOS on Intel Macs",
+        host: ("x86_64", "macos"),
+    },
+    HostArchInfo {
+        arch: HostArch::Aarch64Darwin,
+        str: "aarch64-apple-darwin",
+        description: "macOS on Apple Silicon Macs",
+        host: ("aarch64", "macos"),
+    },
+];
+
+impl HostArch {
+    /// The target matching the machine running the build, if it is supported.
+    pub fn host() -> Option<Self> {
+        let host = (std::env::consts::ARCH, std::env::consts::OS);
+        HOST_ARCHS
+            .iter()
+            .find(|info| info.host == host)
+            .map(|info| info.arch)
+    }
+
+    /// The target named by `s`, ignoring case.
+    pub fn parse(s: &str) -> Option<Self> {
+        HOST_ARCHS
+            .iter()
+            .find(|info| info.str.eq_ignore_ascii_case(s))
+            .map(|info| info.arch)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.info().str
+    }
+
+    /// Extension of the dynamic library built for the target.
+    pub fn lib_extension(&self) -> &'static str {
+        match self {
+            HostArch::X8664Linux | HostArch::Aarch64Linux => "so",
+            HostArch::X8664Darwin | HostArch::Aarch64Darwin => "dylib",
+        }
+    }
+
+    fn info(&self) -> &'static HostArchInfo {
+        &HOST_ARCHS[*self as usize]
+    }
+
+    fn all_strings() -> Vec<&'static str> {
+        HOST_ARCHS.iter().map(|info| info.str).collect()
+    }
+
+    fn all_display_strings() -> Vec<(String, String)> {
+        HOST_ARCHS
+            .iter()
+            .map(|info| (info.str.to_string(), info.description.to_string()))
+            .collect()
+    }
+}
+
+/// Declares the arch of a platform building for the [`HostArch`] targets.
+macro_rules! host_arch {
+    ($name:ident, $platform:expr, $env_var:expr, $label:literal) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(pub HostArch);
+
+        impl $name {
+            /// The target matching the machine running the build, if it is supported.
+            pub fn host() -> Option<Self> {
+                HostArch::host().map(Self)
+            }
+        }
+
+        impl Arch for $name {
+            fn platform() -> Box<dyn Platform> {
+                Box::new($platform)
+            }
+
+            fn as_str(&self) -> &'static str {
+                self.0.as_str()
+            }
+
+            fn parse_from_str<S: AsRef<str>>(s: S) -> Self {
+                HostArch::parse(s.as_ref())
+                    .map(Self)
+                    .context(format!("Unsupported {} Arch '{}'", $label, s.as_ref()))
+                    .unwrap()
+            }
+
+            fn all_strings() -> Vec<&'static str> {
+                HostArch::all_strings()
+            }
+
+            fn all_display_strings() -> Vec<(String, String)> {
+                HostArch::all_display_strings()
+            }
+
+            fn env_var_name() -> &'static str {
+                $env_var
+            }
+        }
+    };
+}
+
+host_arch!(PythonArch, PythonPlatform, PYTHON_ARCHS_ENV, "Python");
+host_arch!(JvmArch, JvmPlatform, JVM_ARCHS_ENV, "JVM");
+host_arch!(NodeArch, NodePlatform, NODE_ARCHS_ENV, "Node");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CArch {
+    X8664Linux,
+    Aarch64Linux,
+    Armv7Linux,
+}
+
+struct CArchInfo {
+    arch: CArch,
+    str: &'static str,
+    description: &'static str,
+}
+
+const C_ARCHS: [CArchInfo; 3] = [
+    CArchInfo {
+        arch: CArch::X8664Linux,
+        str: "x86_64-unknown-linux-gnu",
+        description: "64-bit Linux (x86_64 architecture)",
+    },
+    CArchInfo {
+        arch: CArch::Aarch64Linux,
+        str: "aarch64-unknown-linux-gnu",
+        description: "64-bit Linux (ARM64 architecture)",
+    },
+    CArchInfo {
+        arch: CArch::Armv7Linux,
+        str: "armv7-unknown-linux-gnueabihf",
+        description: "32-bit ARMv7 Linux (hard float, embedded boards)",
+    },
+];
+
+impl Arch for CArch {
+    fn platform() -> Box<dyn Platform> {
+        Box::new(CPlatform)
+    }
+
+    fn as_str(&self) -> &'static str {
+        C_ARCHS
+            .iter()
+            .find(|info| info.arch == *self)
+            .map(|info| info.str)
+            .expect("Unsupported C Arch")
+    }
+
+    fn parse_from_str<S: AsRef<str>>(s: S) -> Self {
+        C_ARCHS
+            .iter()
+            .find(|info| info.str.to_lowercase() == s.as_ref().to_lowercase())
+            .map(|info| info.arch)
+            .context(format!("Unsupported C Arch '{}'", s.as_ref()))
+            .unwrap()
+    }
+
+    fn all_strings() -> Vec<&'static str> {
+        C_ARCHS.iter().map(|info| info.str).collect()
+    }
+
+    fn all_display_strings() -> Vec<(String, String)> {
+        C_ARCHS
+            .iter()
+            .map(|info| (info.str.to_string(), info.description.to_string()))
+            .collect()
+    }
+
+    fn env_var_name() -> &'static str {
+        C_ARCHS_ENV
+    }
+}
+
 //
 // Platform Section
 //
@@ -481,6 +758,18 @@ pub trait PlatformBuilder: Platform {
         target_arch: Vec<Self::Arch>,
         params: Self::Params,
     ) -> anyhow::Result<std::path::PathBuf>;
+
+    /// Describe the commands [`PlatformBuilder::build`] would run with the same
+    /// arguments, without running anything. Builders that don't override it
+    /// can't be dry-run.
+    fn plan(
+        _mode: Mode,
+        _project_dir: &std::path::Path,
+        _target_arch: &[Self::Arch],
+        _params: &Self::Params,
+    ) -> anyhow::Result<super::plan::BuildPlan> {
+        anyhow::bail!("This builder does not support dry runs")
+    }
 }
 
 pub struct IosPlatform;
@@ -522,3 +811,60 @@ impl Platform for ReactNativePlatform {
         "React Native Bindings Builder"
     }
 }
+
+pub struct PythonPlatform;
+
+impl Platform for PythonPlatform {
+    fn identifier() -> &'static str {
+        "Python Bindings Builder"
+    }
+}
+
+pub struct JvmPlatform;
+
+impl Platform for JvmPlatform {
+    fn identifier() -> &'static str {
+        "JVM Bindings Builder"
+    }
+}
+
+pub struct NodePlatform;
+
+impl Platform for NodePlatform {
+    fn identifier() -> &'static str {
+        "Node Bindings Builder"
+    }
+}
+
+pub struct CPlatform;
+
+impl Platform for CPlatform {
+    fn identifier() -> &'static str {
+        "C Bindings Builder"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_parse_from_str() {
+        assert_eq!(Mode::parse_from_str("debug").unwrap(), Mode::Debug);
+        assert_eq!(Mode::parse_from_str("dev").unwrap(), Mode::Debug);
+        assert_eq!(Mode::parse_from_str("Release").unwrap(), Mode::Release);
+        assert_eq!(
+            Mode::parse_from_str("profile:mobile").unwrap(),
+            Mode::Profile("mobile".to_string())
+        );
+        assert_eq!(
+            Mode::parse_from_str("profile:release").unwrap(),
+            Mode::Release
+        );
+
+        assert!(Mode::parse_from_str("relase").is_err());
+        assert!(Mode::parse_from_str("mobile").is_err());
+        assert!(Mode::parse_from_str("profile:").is_err());
+        assert!(Mode::parse_from_str("").is_err());
+    }
+}
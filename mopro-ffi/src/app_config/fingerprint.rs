@@ -0,0 +1,395 @@
+//! Content fingerprints used to skip platforms whose inputs did not change
+//! since their bindings were last built.
+//!
+//! Each target arch gets its own fingerprint. It covers the project sources,
+//! `Cargo.toml` and `Cargo.lock` (and so the enabled features), the path
+//! dependencies and workspace members, any extra inputs such as circuits kept
+//! outside `test-vectors`, the toolchain-related environment, and the steps of
+//! the [`BuildPlan`] that concern the arch, which pin the mode and every
+//! command and flag the builder runs. They are stored in
+//! `build/fingerprints/<platform>/<arch>` once the bindings are in place.
+//!
+//! Builders replace the whole bindings directory, so a platform is skipped
+//! only when all of its archs are fresh; otherwise every arch is rebuilt.
+
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+use super::plan::{BuildPlan, PlanStep};
+
+/// Files and directories of the project hashed into the fingerprint.
+const PROJECT_INPUTS: [&str; 5] = [
+    "Cargo.toml",
+    "Cargo.lock",
+    "build.rs",
+    "src",
+    "test-vectors",
+];
+
+/// Directories skipped when hashing path dependencies and workspace members.
+const SKIPPED_DIRS: [&str; 3] = ["target", "build", ".git"];
+
+/// Dependency tables of a manifest that may hold `path` dependencies.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Environment variables that change the output without changing the plan.
+const ENV_INPUTS: [&str; 5] = [
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "ANDROID_NDK",
+    "ANDROID_NDK_HOME",
+    "IPHONEOS_DEPLOYMENT_TARGET",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    dir: PathBuf,
+    /// Digest of each target arch of the plan.
+    digests: BTreeMap<String, String>,
+}
+
+impl Fingerprint {
+    /// Fingerprint the archs of `plan`. `extra_inputs` are files or
+    /// directories, relative to the project, the build also reads.
+    pub fn compute(
+        project_dir: &Path,
+        plan: &BuildPlan,
+        extra_inputs: &[PathBuf],
+    ) -> anyhow::Result<Self> {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update(&plan.platform);
+        hasher.update(&plan.mode);
+        hasher.update(plan.output.to_string_lossy().as_bytes());
+        for var in ENV_INPUTS {
+            hasher.update(var);
+            hasher.update(std::env::var(var).unwrap_or_default());
+        }
+        for input in PROJECT_INPUTS {
+            hash_path(&mut hasher, project_dir, &project_dir.join(input), &[])
+                .with_context(|| format!("Failed to fingerprint {input}"))?;
+        }
+        for dir in local_crates(project_dir)? {
+            hash_path(&mut hasher, project_dir, &dir, &SKIPPED_DIRS)
+                .with_context(|| format!("Failed to fingerprint {}", dir.display()))?;
+        }
+        for input in extra_inputs {
+            hash_path(&mut hasher, project_dir, &project_dir.join(input), &[])
+                .with_context(|| format!("Failed to fingerprint {}", input.display()))?;
+        }
+
+        let digests = plan
+            .target_archs
+            .iter()
+            .map(|arch| {
+                let mut hasher = hasher.clone();
+                hasher.update(arch);
+                for step in arch_steps(plan, arch) {
+                    hasher.update(format!("{step:?}"));
+                }
+                (arch.clone(), format!("{:x}", hasher.finalize()))
+            })
+            .collect();
+
+        Ok(Self {
+            dir: project_dir
+                .join("build")
+                .join("fingerprints")
+                .join(&plan.platform),
+            digests,
+        })
+    }
+
+    /// Whether every arch matches its recorded fingerprint, no other arch was
+    /// recorded, and `output` is still there.
+    pub fn is_fresh(&self, output: &Path) -> bool {
+        output.exists()
+            && !self.digests.is_empty()
+            && self.stale_archs().is_empty()
+            && self.recorded_archs() == self.digests.keys().cloned().collect()
+    }
+
+    /// The archs whose inputs changed since they were last built.
+    fn stale_archs(&self) -> Vec<&str> {
+        self.digests
+            .iter()
+            .filter(|(arch, digest)| {
+                !fs::read_to_string(self.dir.join(arch))
+                    .is_ok_and(|recorded| recorded.trim() == digest.as_str())
+            })
+            .map(|(arch, _)| arch.as_str())
+            .collect()
+    }
+
+    /// Record the fingerprints after a successful build, replacing those of
+    /// archs the bindings no longer contain. Record the ones computed before
+    /// the build, so that inputs edited while it ran are seen as changed.
+    pub fn record(&self) -> anyhow::Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).context("Failed to clear old fingerprints")?;
+        }
+        fs::create_dir_all(&self.dir).context("Failed to create fingerprints directory")?;
+        for (arch, digest) in &self.digests {
+            fs::write(self.dir.join(arch), digest).context("Failed to write fingerprint")?;
+        }
+        Ok(())
+    }
+
+    fn recorded_archs(&self) -> BTreeSet<String> {
+        fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The steps of `plan` for `arch`: those naming it, and the shared ones that
+/// name no arch at all.
+fn arch_steps<'a>(plan: &'a BuildPlan, arch: &'a str) -> impl Iterator<Item = &'a PlanStep> {
+    plan.steps.iter().filter(move |step| {
+        let tokens = step_tokens(step);
+        tokens.contains(arch) || !plan.target_archs.iter().any(|other| tokens.contains(other))
+    })
+}
+
+/// Words of a step, split so that e.g. `aarch64-apple-ios` does not match
+/// `aarch64-apple-ios-sim`.
+fn step_tokens(step: &PlanStep) -> BTreeSet<String> {
+    format!("{step:?}")
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Directories of the path dependencies, followed transitively, and of the
+/// workspace members of the project.
+fn local_crates(project_dir: &Path) -> anyhow::Result<BTreeSet<PathBuf>> {
+    let project_dir = project_dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", project_dir.display()))?;
+    let mut crates = BTreeSet::new();
+    let mut pending = vec![project_dir.clone()];
+    while let Some(dir) = pending.pop() {
+        let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        let manifest: Value = content
+            .parse()
+            .with_context(|| format!("Failed to parse {}/Cargo.toml", dir.display()))?;
+        for path in manifest_paths(&manifest) {
+            for member in expand_member(&dir.join(path)) {
+                let Ok(member) = member.canonicalize() else {
+                    continue;
+                };
+                if member != project_dir && crates.insert(member.clone()) {
+                    pending.push(member);
+                }
+            }
+        }
+    }
+    Ok(crates)
+}
+
+/// The `path` of every dependency, patch and workspace member of a manifest.
+fn manifest_paths(manifest: &Value) -> Vec<String> {
+    let mut tables = Vec::new();
+    for name in DEPENDENCY_TABLES {
+        tables.extend(manifest.get(name));
+    }
+    if let Some(targets) = manifest.get("target").and_then(Value::as_table) {
+        for target in targets.values() {
+            for name in DEPENDENCY_TABLES {
+                tables.extend(target.get(name));
+            }
+        }
+    }
+    if let Some(patches) = manifest.get("patch").and_then(Value::as_table) {
+        tables.extend(patches.values());
+    }
+    let workspace = manifest.get("workspace");
+    tables.extend(workspace.and_then(|workspace| workspace.get("dependencies")));
+
+    let mut paths: Vec<String> = tables
+        .iter()
+        .filter_map(|table| table.as_table())
+        .flat_map(|table| table.values())
+        .filter_map(|dependency| dependency.get("path")?.as_str())
+        .map(str::to_string)
+        .collect();
+    if let Some(members) = workspace
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(Value::as_array)
+    {
+        paths.extend(members.iter().filter_map(Value::as_str).map(str::to_string));
+    }
+    paths
+}
+
+/// Expand a trailing `*` of a workspace member into the crates it matches.
+fn expand_member(path: &Path) -> Vec<PathBuf> {
+    if path.file_name().is_some_and(|name| name == "*") {
+        let Some(parent) = path.parent() else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(parent) else {
+            return Vec::new();
+        };
+        let mut members: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|dir| dir.join("Cargo.toml").exists())
+            .collect();
+        members.sort();
+        members
+    } else {
+        vec![path.to_path_buf()]
+    }
+}
+
+/// Hash the relative path and contents of `path`, recursing into directories
+/// in a stable order and leaving out the directories named in `skipped`.
+/// Missing inputs are skipped.
+fn hash_path(hasher: &mut Sha256, root: &Path, path: &Path, skipped: &[&str]) -> io::Result<()> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    hasher.update(relative.to_string_lossy().as_bytes());
+
+    if metadata.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            let skip = entry.is_dir()
+                && entry
+                    .file_name()
+                    .is_some_and(|name| skipped.iter().any(|skipped| name == *skipped));
+            if !skip {
+                hash_path(hasher, root, &entry, skipped)?;
+            }
+        }
+    } else {
+        hasher.update(metadata.len().to_le_bytes());
+        io::copy(&mut File::open(path)?, hasher)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::mktemp;
+    use crate::app_config::plan::PlanStep;
+
+    fn digest(root: &Path, path: &Path, skipped: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        hash_path(&mut hasher, root, path, skipped).unwrap();
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn plan(output: &Path, archs: &[&str]) -> BuildPlan {
+        BuildPlan {
+            platform: "test".to_string(),
+            mode: "release".to_string(),
+            target_archs: archs.iter().map(|arch| arch.to_string()).collect(),
+            steps: archs
+                .iter()
+                .map(|arch| PlanStep {
+                    description: format!("Build for {arch}"),
+                    command: None,
+                    inputs: vec![],
+                    outputs: vec![],
+                })
+                .collect(),
+            output: output.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_hash_path() {
+        let root = mktemp().unwrap();
+        fs::create_dir_all(root.join("src/target")).unwrap();
+        fs::write(root.join("src/lib.rs"), "fn a() {}").unwrap();
+        let initial = digest(&root, &root.join("src"), &["target"]);
+
+        // Stable, and blind to skipped directories
+        fs::write(root.join("src/target/out"), "artifact").unwrap();
+        assert_eq!(digest(&root, &root.join("src"), &["target"]), initial);
+        assert_ne!(digest(&root, &root.join("src"), &[]), initial);
+
+        // Covers contents and file names
+        fs::write(root.join("src/lib.rs"), "fn b() {}").unwrap();
+        let edited = digest(&root, &root.join("src"), &["target"]);
+        assert_ne!(edited, initial);
+        fs::rename(root.join("src/lib.rs"), root.join("src/main.rs")).unwrap();
+        assert_ne!(digest(&root, &root.join("src"), &["target"]), edited);
+
+        // A missing input hashes like an empty one
+        assert_eq!(
+            digest(&root, &root.join("missing"), &[]),
+            format!("{:x}", Sha256::new().finalize())
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let root = mktemp().unwrap();
+        let output = root.join("Bindings");
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"a\"\n").unwrap();
+        let archs = ["aarch64-apple-ios", "aarch64-apple-ios-sim"];
+        let fingerprint = Fingerprint::compute(&root, &plan(&output, &archs), &[]).unwrap();
+        assert!(!fingerprint.is_fresh(&output));
+
+        fingerprint.record().unwrap();
+        assert!(!fingerprint.is_fresh(&output), "no output yet");
+        fs::create_dir(&output).unwrap();
+        assert!(fingerprint.is_fresh(&output));
+
+        // Only the arch named by a changed step goes stale
+        let mut changed = plan(&output, &archs);
+        changed.steps[1].description.push_str(" with lto");
+        let fingerprint = Fingerprint::compute(&root, &changed, &[]).unwrap();
+        assert_eq!(fingerprint.stale_archs(), vec!["aarch64-apple-ios-sim"]);
+        assert!(!fingerprint.is_fresh(&output));
+
+        // A subset of the recorded archs does not match the bindings
+        let subset = Fingerprint::compute(&root, &plan(&output, &archs[..1]), &[]).unwrap();
+        assert!(subset.stale_archs().is_empty());
+        assert!(!subset.is_fresh(&output));
+
+        // Path dependencies and extra inputs are inputs too
+        fs::create_dir_all(root.join("dep/src")).unwrap();
+        fs::create_dir_all(root.join("circuits")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"a\"\n[dependencies]\ndep = { path = \"dep\" }\n",
+        )
+        .unwrap();
+        fs::write(root.join("dep/Cargo.toml"), "[package]\nname = \"dep\"\n").unwrap();
+        let extra = [PathBuf::from("circuits")];
+        let fingerprint = Fingerprint::compute(&root, &plan(&output, &archs), &extra).unwrap();
+        fingerprint.record().unwrap();
+        assert!(fingerprint.is_fresh(&output));
+        fs::write(root.join("dep/src/lib.rs"), "fn a() {}").unwrap();
+        let fingerprint = Fingerprint::compute(&root, &plan(&output, &archs), &extra).unwrap();
+        assert!(!fingerprint.is_fresh(&output));
+        fingerprint.record().unwrap();
+        fs::write(root.join("circuits/main.circom"), "template A() {}").unwrap();
+        let fingerprint = Fingerprint::compute(&root, &plan(&output, &archs), &extra).unwrap();
+        assert!(!fingerprint.is_fresh(&output));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
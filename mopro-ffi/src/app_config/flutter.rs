@@ -6,14 +6,15 @@ use toml::Value;
 
 use crate::app_config::constants::{FlutterArch, FlutterPlatform, Mode, FLUTTER_BINDINGS_DIR};
 
+use super::plan::BuildPlan;
 use super::raw_project_name_from_toml;
-use super::PlatformBuilder;
+use super::{command, PlatformBuilder};
 
 // Maintained for backwards compatibility
 #[inline]
-pub fn build() {
+pub fn build() -> anyhow::Result<()> {
     if cfg!(feature = "uniffi") {
-        panic!("\"uniffi\" and \"flutter\" features cannot be enabled at the same time, please disable one of them in your Cargo.toml");
+        anyhow::bail!("\"uniffi\" and \"flutter\" features cannot be enabled at the same time, please disable one of them in your Cargo.toml");
     }
     super::build_from_env::<FlutterPlatform>()
 }
@@ -35,26 +36,12 @@ impl PlatformBuilder for FlutterPlatform {
         let cargo_toml_path = project_dir
             .join(FLUTTER_BINDINGS_DIR)
             .join("rust/Cargo.toml");
-        ensure_workspace_toml(cargo_toml_path.to_string_lossy().as_ref());
+        ensure_workspace_toml(&cargo_toml_path)?;
 
         // Import user defined crates
         let third_party_crate_name = raw_project_name_from_toml(project_dir)?;
-        let cargo_add_status = Command::new("cargo")
-            .args([
-                "add",
-                &third_party_crate_name,
-                "--path",
-                project_dir.to_string_lossy().as_ref(),
-                "--no-default-features",
-                "--features",
-                "flutter",
-            ])
-            .current_dir(project_dir.join(FLUTTER_BINDINGS_DIR).join("rust"))
-            .status()
-            .expect("failed to run cargo add");
-        if !cargo_add_status.success() {
-            return Err(anyhow::anyhow!("Failed to add third party crate"));
-        }
+        command::run(&mut cargo_add_command(project_dir, &third_party_crate_name))
+            .context("Failed to add third party crate")?;
 
         // Replace relative path with absolute path
         replace_relative_path_with_absolute(
@@ -78,27 +65,92 @@ impl PlatformBuilder for FlutterPlatform {
         copy_libcxx_shared_so_to_jni_libs(project_dir)?;
 
         // Generate flutter bindings
-        let rust_root = project_dir.join(FLUTTER_BINDINGS_DIR).join("rust");
-        let dart_output = project_dir.join(FLUTTER_BINDINGS_DIR).join("lib/src/rust");
-        let generate_status = Command::new("flutter_rust_bridge_codegen")
-            .args(["generate"])
-            .args([
-                "--rust-root",
-                &rust_root.to_string_lossy(),
-                "--rust-input",
-                &third_party_crate_name,
-                "--dart-output",
-                &dart_output.to_string_lossy(),
-            ])
-            .current_dir(project_dir)
-            .status()
-            .expect("failed to run flutter_rust_bridge_codegen");
-        if !generate_status.success() {
-            return Err(anyhow::anyhow!("Failed to generate simple.rs"));
-        }
+        command::run(&mut codegen_generate_command(
+            project_dir,
+            &third_party_crate_name,
+        ))
+        .context("Failed to generate flutter bindings")?;
 
         Ok(PathBuf::from(FLUTTER_BINDINGS_DIR))
     }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        _params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let third_party_crate_name = raw_project_name_from_toml(project_dir)?;
+        let bindings_dir = project_dir.join(FLUTTER_BINDINGS_DIR);
+        let rust_cargo_toml = bindings_dir.join("rust/Cargo.toml");
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dir);
+        plan.step(
+            "Install flutter_rust_bridge_codegen if it is missing",
+            vec![],
+            vec![],
+        );
+        if !bindings_dir.exists() {
+            plan.command(
+                "Create the Flutter plugin template",
+                &codegen_create_command(),
+                vec![],
+                vec![bindings_dir.clone()],
+            );
+        }
+        plan.command(
+            format!("Add {third_party_crate_name} to the plugin crate"),
+            &cargo_add_command(project_dir, &third_party_crate_name),
+            vec![project_dir.join("Cargo.toml")],
+            vec![rust_cargo_toml],
+        );
+        plan.step(
+            "Patch the cargokit build script and the iOS podspec",
+            vec![],
+            vec![
+                bindings_dir.join("cargokit/gradle/plugin.gradle"),
+                bindings_dir
+                    .join("ios")
+                    .join(format!("{FLUTTER_BINDINGS_DIR}.podspec")),
+            ],
+        );
+        plan.command(
+            "Generate the Dart bindings",
+            &codegen_generate_command(project_dir, &third_party_crate_name),
+            vec![project_dir.to_path_buf()],
+            vec![bindings_dir.join("lib/src/rust")],
+        );
+        Ok(plan)
+    }
+}
+
+fn cargo_add_command(project_dir: &Path, crate_name: &str) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(["add", crate_name, "--path"])
+        .arg(project_dir)
+        .args(["--no-default-features", "--features", "flutter"])
+        .current_dir(project_dir.join(FLUTTER_BINDINGS_DIR).join("rust"));
+    cmd
+}
+
+fn codegen_create_command() -> Command {
+    let mut cmd = Command::new("flutter_rust_bridge_codegen");
+    cmd.args(["create", FLUTTER_BINDINGS_DIR, "--template", "plugin"]);
+    cmd
+}
+
+fn codegen_generate_command(project_dir: &Path, crate_name: &str) -> Command {
+    let rust_root = project_dir.join(FLUTTER_BINDINGS_DIR).join("rust");
+    let dart_output = project_dir.join(FLUTTER_BINDINGS_DIR).join("lib/src/rust");
+    let mut cmd = Command::new("flutter_rust_bridge_codegen");
+    cmd.arg("generate")
+        .arg("--rust-root")
+        .arg(rust_root)
+        .args(["--rust-input", crate_name])
+        .arg("--dart-output")
+        .arg(dart_output)
+        .current_dir(project_dir);
+    cmd
 }
 
 fn install_flutter_rust_bridge_codegen() -> anyhow::Result<()> {
@@ -112,15 +164,10 @@ fn install_flutter_rust_bridge_codegen() -> anyhow::Result<()> {
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             // Command not found, proceed with installation
             println!("flutter_rust_bridge_codegen not found, installing...");
-            let status = Command::new("cargo")
-                .args(["install", "flutter_rust_bridge_codegen@=2.11.1"])
-                .status()
-                .expect("failed to run flutter_rust_bridge_codegen");
-            if !status.success() {
-                return Err(anyhow::anyhow!(
-                    "Failed to install flutter_rust_bridge_codegen"
-                ));
-            }
+            command::run(
+                Command::new("cargo").args(["install", "flutter_rust_bridge_codegen@=2.11.1"]),
+            )
+            .context("Failed to install flutter_rust_bridge_codegen")?;
         }
         Err(e) => {
             // Other error, propagate it
@@ -140,26 +187,21 @@ fn init_flutter_bindings(project_dir: &Path) -> anyhow::Result<()> {
     install_flutter_rust_bridge_codegen()?;
 
     if !flutter_bindings_dir.exists() {
-        let status = Command::new("flutter_rust_bridge_codegen")
-            .args(["create", FLUTTER_BINDINGS_DIR, "--template", "plugin"])
-            .status()
-            .expect("failed to run flutter_rust_bridge_codegen");
-
-        if !status.success() {
-            return Err(anyhow::anyhow!("flutter_rust_bridge_codegen failed"));
-        }
+        command::run(&mut codegen_create_command())
+            .context("Failed to create the flutter bindings template")?;
     }
 
     Ok(())
 }
 
-fn ensure_workspace_toml(cargo_toml_path: &str) {
-    let content = fs::read_to_string(cargo_toml_path).expect("Failed to read Cargo.toml");
+fn ensure_workspace_toml(cargo_toml_path: &Path) -> anyhow::Result<()> {
+    let content = fs::read_to_string(cargo_toml_path).context("Failed to read Cargo.toml")?;
 
     if !content.contains("[workspace]") {
         let new_content = format!("{content}\n\n[workspace]\n");
-        fs::write(cargo_toml_path, new_content).expect("Failed to write updated Cargo.toml");
+        fs::write(cargo_toml_path, new_content).context("Failed to write updated Cargo.toml")?;
     }
+    Ok(())
 }
 
 fn replace_relative_path_with_absolute(
@@ -8,21 +8,54 @@ use uniffi::{generate, GenerateOptions, TargetLanguage};
 
 use super::constants::{
     Arch, IosArch, IosPlatform, Mode, ARCH_ARM_64, ARCH_X86_64, IOS_BINDINGS_DIR, IOS_SWIFT_FILE,
-    IOS_XCFRAMEWORKS_DIR,
+    IOS_SWIFT_PACKAGE_DIST_DIR, IOS_SWIFT_PACKAGE_FILE, IOS_XCFRAMEWORKS_DIR,
 };
-use super::mktemp_local;
-use super::{cleanup_tmp_local, project_name_from_toml};
-use super::{install_arch, PlatformBuilder};
+use super::plan::BuildPlan;
+use super::{arch_target_dir, build_archs, cargo_build_lib_command, install_arch_command};
+use super::{cleanup_tmp_local, command, move_bindings_into_place};
+use super::{create_staging_dir, staging_dir};
+use super::{install_arch, ArchOverrides, PlatformBuilder};
+use super::{project_name_from_toml, version_from_toml};
 
 // Maintained for backwards compatibility
 #[inline]
-pub fn build() {
+pub fn build() -> anyhow::Result<()> {
     super::build_from_env::<IosPlatform>()
 }
 
+/// Names of the generated iOS artifacts. Give each library its own names to
+/// ship several mopro libraries in one app.
+#[derive(Clone, Debug)]
+pub struct IosBindingsNames {
+    /// Output directory, also the Swift package and module name.
+    pub bindings_dir: String,
+    pub swift_file: String,
+    pub xcframework: String,
+}
+
+impl Default for IosBindingsNames {
+    fn default() -> Self {
+        Self {
+            bindings_dir: IOS_BINDINGS_DIR.to_string(),
+            swift_file: IOS_SWIFT_FILE.to_string(),
+            xcframework: IOS_XCFRAMEWORKS_DIR.to_string(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct IosBindingsParams {
     pub using_noir: bool,
+    pub names: IosBindingsNames,
+    /// Base URL the zipped xcframework will be published under. When set, a
+    /// `dist/Package.swift` consuming the release zip by URL and checksum is
+    /// generated next to the zip.
+    pub swift_package_url: Option<String>,
+    /// Number of architectures built at once, each in its own target dir.
+    /// `0` or `1` builds them one after another.
+    pub jobs: usize,
+    /// Per-target-triple overrides, keyed by [`IosArch::as_str`].
+    pub arch_overrides: ArchOverrides,
 }
 
 impl PlatformBuilder for IosPlatform {
@@ -36,12 +69,12 @@ impl PlatformBuilder for IosPlatform {
         params: Self::Params,
     ) -> anyhow::Result<PathBuf> {
         let uniffi_style_identifier = project_name_from_toml(project_dir)
-            .expect("Failed to get project name from Cargo.toml");
+            .context("Failed to get project name from Cargo.toml")?;
 
-        // Names for the files that will be outputted (can be changed)
-        let bindings_dir_name = IOS_BINDINGS_DIR;
-        let framework_name = IOS_XCFRAMEWORKS_DIR;
-        let out_swift_file_name = IOS_SWIFT_FILE;
+        // Names for the files that will be outputted
+        let bindings_dir_name = params.names.bindings_dir.as_str();
+        let framework_name = params.names.xcframework.as_str();
+        let out_swift_file_name = params.names.swift_file.as_str();
 
         // Names for the generated files by uniffi
         let gen_swift_file_name = format!("{uniffi_style_identifier}.swift");
@@ -51,80 +84,54 @@ impl PlatformBuilder for IosPlatform {
 
         // Paths for the generated files
         let build_dir_path = project_dir.join("build");
-        let work_dir = mktemp_local(&build_dir_path);
+        let work_dir = create_staging_dir(&build_dir_path, bindings_dir_name)?;
         let swift_bindings_dir = work_dir.join(Path::new("SwiftBindings"));
         let bindings_out = work_dir.join(bindings_dir_name);
-        fs::create_dir(&bindings_out).expect("Failed to create bindings out directory");
+        fs::create_dir(&bindings_out).context("Failed to create bindings out directory")?;
         let bindings_dest = Path::new(&project_dir).join(bindings_dir_name);
         let framework_out = bindings_out.join(framework_name);
 
-        // Take a list of architectures, build them, and combine them into
-        // a single universal binary/archive
-        let build_combined_archs = |archs: &[IosArch]| -> PathBuf {
-            let out_lib_paths: Vec<PathBuf> = archs
-                .iter()
-                .map(|arch| {
-                    Path::new(&build_dir_path).join(format!(
-                        "{}/{}/{}",
-                        arch.as_str(),
-                        mode.as_str(),
-                        lib_name
-                    ))
-                })
-                .collect();
-            for arch in archs {
-                install_arch(arch.as_str().to_string());
-                let mut build_cmd = Command::new("cargo");
-                build_cmd.arg("build");
-                if mode == Mode::Release {
-                    build_cmd.arg("--release");
-                }
-                // The dependencies of Noir libraries need iOS 15 and above.
-                if params.using_noir {
-                    build_cmd.env("IPHONEOS_DEPLOYMENT_TARGET", "15.0");
-                }
-                build_cmd
-                    .arg("--lib")
-                    .env("CARGO_BUILD_TARGET_DIR", &build_dir_path)
-                    .env("CARGO_BUILD_TARGET", arch.as_str())
-                    .spawn()
-                    .expect("Failed to spawn cargo build")
-                    .wait()
-                    .expect("cargo build errored");
-            }
-            // now lipo the libraries together
-            let mut lipo_cmd = Command::new("lipo");
-            let lib_out = mktemp_local(&build_dir_path).join(lib_name.clone());
-            lipo_cmd
-                .arg("-create")
-                .arg("-output")
-                .arg(lib_out.to_str().unwrap());
-            for p in out_lib_paths {
-                lipo_cmd.arg(p.to_str().unwrap());
-            }
-            lipo_cmd
-                .spawn()
-                .expect("Failed to spawn lipo")
-                .wait()
-                .expect("lipo command failed");
-
-            lib_out
-        };
-
-        let out_lib_paths: Vec<PathBuf> = group_target_archs(&target_archs)
+        for arch in &target_archs {
+            install_arch(arch.as_str().to_string())?;
+        }
+        build_archs(&target_archs, params.jobs, |arch| {
+            command::run(&mut cargo_build_command(
+                arch,
+                &build_dir_path,
+                &mode,
+                &params,
+            ))
+            .with_context(|| format!("cargo build failed for {}", arch.as_str()))?;
+            Ok(())
+        })?;
+
+        // Combine the libraries of each group of architectures into a single
+        // universal binary/archive
+        let out_lib_paths: Vec<PathBuf> = group_target_archs(&target_archs)?
             .iter()
-            .map(|v| build_combined_archs(v))
-            .collect();
-
-        let out_dylib_path = build_dir_path.join(format!(
-            "{}/{}/{}",
-            target_archs[0].as_str(),
-            mode.as_str(),
-            lib_name.replace(".a", ".dylib")
-        ));
+            .map(|archs| -> anyhow::Result<PathBuf> {
+                let arch_lib_paths: Vec<PathBuf> = archs
+                    .iter()
+                    .map(|&arch| arch_lib_path(arch, &build_dir_path, &mode, &lib_name, &params))
+                    .collect();
+                let lib_out = universal_lib_path(&work_dir, archs, &lib_name);
+                fs::create_dir_all(lib_out.parent().unwrap())
+                    .context("Failed to create universal library directory")?;
+                command::run(&mut lipo_command(&lib_out, &arch_lib_paths))?;
+                Ok(lib_out)
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let out_dylib_path = arch_lib_path(
+            target_archs[0],
+            &build_dir_path,
+            &mode,
+            &lib_name.replace(".a", ".dylib"),
+            &params,
+        );
 
         generate_ios_bindings(&out_dylib_path, &swift_bindings_dir)
-            .expect("Failed to generate bindings for iOS");
+            .context("Failed to generate bindings for iOS")?;
 
         fs::rename(
             swift_bindings_dir.join(&gen_swift_file_name),
@@ -135,26 +142,13 @@ impl PlatformBuilder for IosPlatform {
             swift_bindings_dir.display(),
         ))?;
 
-        let mut xcbuild_cmd = Command::new("xcodebuild");
-        // The dependencies of Noir libraries need iOS 15 and above.
-        if params.using_noir {
-            xcbuild_cmd.env("IPHONEOS_DEPLOYMENT_TARGET", "15.0");
-        }
-        xcbuild_cmd.arg("-create-xcframework");
-        for lib_path in out_lib_paths {
-            xcbuild_cmd
-                .arg("-library")
-                .arg(lib_path.to_str().unwrap())
-                .arg("-headers")
-                .arg(swift_bindings_dir.to_str().unwrap());
-        }
-        xcbuild_cmd
-            .arg("-output")
-            .arg(framework_out.to_str().unwrap())
-            .spawn()
-            .context("Failed to spawn xcodebuild")?
-            .wait()
-            .context("xcodebuild command failed")?;
+        command::run(&mut xcframework_command(
+            &out_lib_paths,
+            &swift_bindings_dir,
+            &framework_out,
+            params.using_noir,
+        ))
+        .context("Failed to create the xcframework")?;
 
         // Swift requires module maps named "module.modulemap", but uniffi uses "<placeholder>FFI.modulemap".
         // To support multiple libraries in the same project without naming conflicts,
@@ -165,25 +159,206 @@ impl PlatformBuilder for IosPlatform {
             &modulemap_name,
             &uniffi_style_identifier,
         )
-        .expect("Failed to generate header artifacts");
+        .context("Failed to generate header artifacts")?;
+
+        let version = version_from_toml(project_dir)?;
+        generate_swift_package(&bindings_out, &version, &params)
+            .context("Failed to generate Swift package")?;
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir_path)?;
+
+        Ok(bindings_dest)
+    }
 
-        if let Ok(info) = fs::metadata(&bindings_dest) {
-            if !info.is_dir() {
-                panic!("framework directory exists and is not a directory");
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let names = &params.names;
+        let lib_name = format!("lib{uniffi_style_identifier}.a");
+        let build_dir_path = project_dir.join("build");
+        let bindings_dest = project_dir.join(&names.bindings_dir);
+        let work_dir = staging_dir(&build_dir_path, &names.bindings_dir);
+        let bindings_out = work_dir.join(&names.bindings_dir);
+        let framework_out = bindings_out.join(&names.xcframework);
+        let swift_bindings_dir = work_dir.join("SwiftBindings");
+        let first_arch = target_archs
+            .first()
+            .context("No target architectures provided")?;
+
+        let version = version_from_toml(project_dir)?;
+        let dist_dir = bindings_out.join(IOS_SWIFT_PACKAGE_DIST_DIR);
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        let mut universal_libs = Vec::new();
+        for archs in group_target_archs(target_archs)? {
+            let lib_out = universal_lib_path(&work_dir, &archs, &lib_name);
+            let mut arch_libs = Vec::new();
+            for arch in archs {
+                let arch_str = arch.as_str();
+                let out_lib_path = arch_lib_path(arch, &build_dir_path, &mode, &lib_name, params);
+                plan.command(
+                    format!("Install the {arch_str} target"),
+                    &install_arch_command(arch_str),
+                    vec![],
+                    vec![],
+                );
+                plan.command(
+                    format!("Build for {arch_str}"),
+                    &cargo_build_command(arch, &build_dir_path, &mode, params),
+                    vec![project_dir.to_path_buf()],
+                    vec![out_lib_path.clone()],
+                );
+                arch_libs.push(out_lib_path);
             }
-            fs::remove_dir_all(&bindings_dest).expect("Failed to remove framework directory");
+            plan.command(
+                "Combine the libraries into a universal archive",
+                &lipo_command(&lib_out, &arch_libs),
+                arch_libs,
+                vec![lib_out.clone()],
+            );
+            universal_libs.push(lib_out);
         }
 
-        fs::rename(&bindings_out, &bindings_dest).expect("Failed to move framework into place");
-        // Copy the mopro.swift file to the output directory
-        cleanup_tmp_local(&build_dir_path);
+        let out_dylib_path = arch_lib_path(
+            *first_arch,
+            &build_dir_path,
+            &mode,
+            &lib_name.replace(".a", ".dylib"),
+            params,
+        );
+        plan.step(
+            "Generate the Swift bindings with UniFFI",
+            vec![out_dylib_path],
+            vec![
+                bindings_out.join(&names.swift_file),
+                swift_bindings_dir.clone(),
+            ],
+        );
+        plan.command(
+            "Create the xcframework",
+            &xcframework_command(
+                &universal_libs,
+                &swift_bindings_dir,
+                &framework_out,
+                params.using_noir,
+            ),
+            universal_libs.clone(),
+            vec![framework_out.clone()],
+        );
+        plan.step(
+            "Regroup the headers and module maps",
+            vec![framework_out.clone()],
+            vec![framework_out.clone()],
+        );
+
+        let zip_path = dist_dir.join(swift_package_zip_name(names, &version));
+        plan.step(
+            "Write Package.swift",
+            vec![],
+            vec![bindings_out.join(IOS_SWIFT_PACKAGE_FILE)],
+        );
+        plan.command(
+            "Zip the xcframework",
+            &zip_command(&bindings_out, &names.xcframework, &zip_path),
+            vec![framework_out],
+            vec![zip_path.clone()],
+        );
+        let mut checksum_path = zip_path.clone().into_os_string();
+        checksum_path.push(".sha256");
+        plan.command(
+            "Compute the checksum of the zip",
+            &checksum_command(&zip_path),
+            vec![zip_path],
+            vec![checksum_path.into()],
+        );
+        if params.swift_package_url.is_some() {
+            plan.step(
+                "Write the release Package.swift",
+                vec![],
+                vec![
+                    dist_dir.join(IOS_SWIFT_PACKAGE_FILE),
+                    dist_dir.join(&names.swift_file),
+                ],
+            );
+        }
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
 
-        Ok(bindings_dest)
+fn cargo_build_command(
+    arch: IosArch,
+    build_dir: &Path,
+    mode: &Mode,
+    params: &IosBindingsParams,
+) -> Command {
+    let arch_str = arch.as_str();
+    let mut build_cmd = cargo_build_lib_command(
+        arch_str,
+        &arch_target_dir(build_dir, arch_str, params.jobs),
+        mode,
+        &params.arch_overrides,
+    );
+    // The dependencies of Noir libraries need iOS 15 and above.
+    if params.using_noir {
+        build_cmd.env("IPHONEOS_DEPLOYMENT_TARGET", "15.0");
     }
+    build_cmd
+}
+
+fn arch_lib_path(
+    arch: IosArch,
+    build_dir: &Path,
+    mode: &Mode,
+    lib_name: &str,
+    params: &IosBindingsParams,
+) -> PathBuf {
+    let arch_str = arch.as_str();
+    arch_target_dir(build_dir, arch_str, params.jobs)
+        .join(arch_str)
+        .join(mode.as_str())
+        .join(lib_name)
+}
+
+fn lipo_command(lib_out: &Path, lib_paths: &[PathBuf]) -> Command {
+    let mut lipo_cmd = Command::new("lipo");
+    lipo_cmd.arg("-create").arg("-output").arg(lib_out);
+    for p in lib_paths {
+        lipo_cmd.arg(p);
+    }
+    lipo_cmd
+}
+
+fn xcframework_command(
+    lib_paths: &[PathBuf],
+    headers_dir: &Path,
+    framework_out: &Path,
+    using_noir: bool,
+) -> Command {
+    let mut xcbuild_cmd = Command::new("xcodebuild");
+    // The dependencies of Noir libraries need iOS 15 and above.
+    if using_noir {
+        xcbuild_cmd.env("IPHONEOS_DEPLOYMENT_TARGET", "15.0");
+    }
+    xcbuild_cmd.arg("-create-xcframework");
+    for lib_path in lib_paths {
+        xcbuild_cmd
+            .arg("-library")
+            .arg(lib_path)
+            .arg("-headers")
+            .arg(headers_dir);
+    }
+    xcbuild_cmd.arg("-output").arg(framework_out);
+    xcbuild_cmd
 }
 
 // More general cases
-fn group_target_archs(target_archs: &[IosArch]) -> Vec<Vec<IosArch>> {
+fn group_target_archs(target_archs: &[IosArch]) -> anyhow::Result<Vec<Vec<IosArch>>> {
     // Detect the current architecture
     let current_arch = std::env::consts::ARCH;
 
@@ -191,7 +366,7 @@ fn group_target_archs(target_archs: &[IosArch]) -> Vec<Vec<IosArch>> {
     let device_prefix = match current_arch {
         arch if arch.starts_with(ARCH_X86_64) => ARCH_X86_64,
         arch if arch.starts_with(ARCH_ARM_64) => ARCH_ARM_64,
-        _ => panic!("Unsupported host architecture: {current_arch}"),
+        _ => anyhow::bail!("Unsupported host architecture: {current_arch}"),
     };
 
     let mut device_archs = Vec::new();
@@ -216,7 +391,7 @@ fn group_target_archs(target_archs: &[IosArch]) -> Vec<Vec<IosArch>> {
         grouped_archs.push(simulator_archs);
     }
 
-    grouped_archs
+    Ok(grouped_archs)
 }
 
 /// Iterate over all architecture entries inside the .xcframework
@@ -266,6 +441,159 @@ pub fn regroup_header_artifacts(
     Ok(())
 }
 
+/// Make the bindings directory a Swift package and zip the xcframework for
+/// release, so the bindings can be added with `.package(path:)` or by URL:
+///
+/// ```text
+/// MoproiOSBindings/
+/// ├── Package.swift                       (binary target by path)
+/// ├── mopro.swift
+/// ├── MoproBindings.xcframework
+/// └── dist/
+///     ├── MoproBindings-<version>.xcframework.zip
+///     ├── MoproBindings-<version>.xcframework.zip.sha256
+///     ├── Package.swift                   (binary target by URL, if requested)
+///     └── mopro.swift
+/// ```
+fn generate_swift_package(
+    bindings_out: &Path,
+    version: &str,
+    params: &IosBindingsParams,
+) -> anyhow::Result<()> {
+    let names = &params.names;
+    let binary_target = names.xcframework.trim_end_matches(".xcframework");
+    // The dependencies of Noir libraries need iOS 15 and above.
+    let ios_version = if params.using_noir { "v15" } else { "v13" };
+
+    let local_target = format!(
+        r#".binaryTarget(name: "{binary_target}", path: "{}")"#,
+        names.xcframework
+    );
+    fs::write(
+        bindings_out.join(IOS_SWIFT_PACKAGE_FILE),
+        swift_package_manifest(
+            names,
+            &local_target,
+            ios_version,
+            &[&names.xcframework, IOS_SWIFT_PACKAGE_DIST_DIR],
+        ),
+    )
+    .context("Failed to write Package.swift")?;
+
+    let dist_dir = bindings_out.join(IOS_SWIFT_PACKAGE_DIST_DIR);
+    fs::create_dir_all(&dist_dir).context("Failed to create dist directory")?;
+    let zip_name = swift_package_zip_name(names, version);
+    let zip_path = dist_dir.join(&zip_name);
+
+    command::run(&mut zip_command(
+        bindings_out,
+        &names.xcframework,
+        &zip_path,
+    ))
+    .with_context(|| format!("Failed to zip {}", names.xcframework))?;
+
+    let output = command::run_captured(&mut checksum_command(&zip_path))
+        .with_context(|| format!("Failed to compute checksum of {zip_name}"))?;
+    let checksum = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let checksum_name = format!("{zip_name}.sha256");
+    fs::write(dist_dir.join(&checksum_name), format!("{checksum}\n"))
+        .context("Failed to write checksum")?;
+
+    if let Some(base_url) = &params.swift_package_url {
+        let url = format!("{}/{zip_name}", base_url.trim_end_matches('/'));
+        let release_excludes = [zip_name.as_str(), checksum_name.as_str()];
+        let remote_target = format!(
+            r#".binaryTarget(name: "{binary_target}", url: "{url}", checksum: "{checksum}")"#
+        );
+        fs::write(
+            dist_dir.join(IOS_SWIFT_PACKAGE_FILE),
+            swift_package_manifest(names, &remote_target, ios_version, &release_excludes),
+        )
+        .context("Failed to write release Package.swift")?;
+        fs::copy(
+            bindings_out.join(&names.swift_file),
+            dist_dir.join(&names.swift_file),
+        )
+        .context("Failed to copy Swift bindings into dist")?;
+    }
+
+    Ok(())
+}
+
+fn swift_package_zip_name(names: &IosBindingsNames, version: &str) -> String {
+    let binary_target = names.xcframework.trim_end_matches(".xcframework");
+    format!("{binary_target}-{version}.xcframework.zip")
+}
+
+/// Universal library of a group of architectures in the staging directory,
+/// e.g. `lib/aarch64-apple-ios-sim+x86_64-apple-ios/lib<name>.a`.
+fn universal_lib_path(work_dir: &Path, archs: &[IosArch], lib_name: &str) -> PathBuf {
+    work_dir.join("lib").join(group_name(archs)).join(lib_name)
+}
+
+fn group_name(archs: &[IosArch]) -> String {
+    archs
+        .iter()
+        .map(|arch| arch.as_str())
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// `ditto` keeps the framework's symlinks and permissions, as Xcode expects.
+fn zip_command(bindings_dir: &Path, xcframework: &str, zip_path: &Path) -> Command {
+    let mut cmd = Command::new("ditto");
+    cmd.args(["-c", "-k", "--sequesterRsrc", "--keepParent"])
+        .arg(xcframework)
+        .arg(zip_path)
+        .current_dir(bindings_dir);
+    cmd
+}
+
+fn checksum_command(zip_path: &Path) -> Command {
+    let mut cmd = Command::new("swift");
+    cmd.args(["package", "compute-checksum"]).arg(zip_path);
+    cmd
+}
+
+fn swift_package_manifest(
+    names: &IosBindingsNames,
+    binary_target: &str,
+    ios_version: &str,
+    exclude: &[&str],
+) -> String {
+    let package_name = &names.bindings_dir;
+    let swift_file = &names.swift_file;
+    let binary_name = names.xcframework.trim_end_matches(".xcframework");
+    let exclude = exclude
+        .iter()
+        .map(|path| format!("\"{path}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"// swift-tools-version:5.5
+import PackageDescription
+
+let package = Package(
+    name: "{package_name}",
+    platforms: [.iOS(.{ios_version})],
+    products: [
+        .library(name: "{package_name}", targets: ["{package_name}"]),
+    ],
+    targets: [
+        {binary_target},
+        .target(
+            name: "{package_name}",
+            dependencies: ["{binary_name}"],
+            path: ".",
+            exclude: [{exclude}],
+            sources: ["{swift_file}"]
+        ),
+    ]
+)
+"#
+    )
+}
+
 fn generate_ios_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::Result<()> {
     if binding_dir.exists() {
         fs::remove_dir_all(binding_dir)?;
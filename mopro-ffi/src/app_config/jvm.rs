@@ -0,0 +1,250 @@
+use anyhow::Context;
+use camino::Utf8Path;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use uniffi::{generate, GenerateOptions, TargetLanguage};
+
+use super::android::reformat_kotlin_package;
+use super::constants::{
+    Arch, HostArch, JvmArch, JvmPlatform, Mode, ANDROID_KT_FILE, ANDROID_PACKAGE_NAME,
+    JVM_BINDINGS_DIR, JVM_NATIVES_JAR, JVM_RESOURCES_DIR,
+};
+use super::plan::BuildPlan;
+use super::project_name_from_toml;
+use super::ArchOverrides;
+use super::{cargo_build_lib_command, install_arch_command, move_bindings_into_place};
+use super::{
+    cleanup_tmp_local, command, create_staging_dir, install_arch, staging_dir, PlatformBuilder,
+};
+
+/// Names of the generated JVM artifacts. The Kotlin package and file default
+/// to the Android ones, so both bindings share their sources.
+#[derive(Clone, Debug)]
+pub struct JvmBindingsNames {
+    pub bindings_dir: String,
+    /// Kotlin package under `uniffi.`, e.g. `mopro` for `uniffi.mopro`.
+    pub package_name: String,
+    pub kt_file: String,
+}
+
+impl Default for JvmBindingsNames {
+    fn default() -> Self {
+        Self {
+            bindings_dir: JVM_BINDINGS_DIR.to_string(),
+            package_name: ANDROID_PACKAGE_NAME.to_string(),
+            kt_file: ANDROID_KT_FILE.to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct JvmBindingsParams {
+    pub names: JvmBindingsNames,
+    /// Per-target-triple overrides, keyed by [`JvmArch::as_str`].
+    pub arch_overrides: ArchOverrides,
+}
+
+impl PlatformBuilder for JvmPlatform {
+    type Arch = JvmArch;
+    type Params = JvmBindingsParams;
+
+    /// Build the library for desktop targets and package it for JNA, which
+    /// the UniFFI Kotlin bindings use on the JVM as well as on Android:
+    ///
+    /// ```text
+    /// MoproJvmBindings/
+    /// ├── uniffi/mopro/mopro.kt
+    /// ├── resources/<jna prefix>/lib<name>.so (or .dylib)
+    /// └── mopro-natives.jar
+    /// ```
+    ///
+    /// JNA finds the libraries on the classpath by their resource prefix
+    /// (e.g. `linux-x86-64/`), so adding the JAR next to `mopro.kt` is enough.
+    fn build(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: Vec<Self::Arch>,
+        params: Self::Params,
+    ) -> anyhow::Result<PathBuf> {
+        let uniffi_style_identifier = project_name_from_toml(project_dir)
+            .context("Failed to get project name from Cargo.toml")?;
+
+        // Names for the generated files by uniffi
+        let gen_kt_file_name = format!("{uniffi_style_identifier}.kt");
+
+        // Paths for the generated files
+        let build_dir = project_dir.join("build");
+        let work_dir = create_staging_dir(&build_dir, &params.names.bindings_dir)?;
+        let names = &params.names;
+        let bindings_out = work_dir.join(&names.bindings_dir);
+        let resources_out = bindings_out.join(JVM_RESOURCES_DIR);
+        let bindings_dest = project_dir.join(&names.bindings_dir);
+
+        let mut bindgen_lib_path = None;
+        for arch in target_archs {
+            let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+            let out_lib_path =
+                build_for_arch(arch, &lib_name, &build_dir, &mode, &params.arch_overrides)
+                    .context(format!(
+                        "Failed to build for architecture: {}",
+                        arch.as_str()
+                    ))?;
+
+            let lib_dest = resources_out.join(jna_prefix(arch)).join(&lib_name);
+            fs::create_dir_all(resources_out.join(jna_prefix(arch)))
+                .context("Failed to create JNA resource directory")?;
+            fs::copy(&out_lib_path, &lib_dest).context("Failed to copy library")?;
+            bindgen_lib_path.get_or_insert(out_lib_path);
+        }
+        let bindgen_lib_path =
+            bindgen_lib_path.context("No target architectures provided for binding generation")?;
+
+        generate_jvm_bindings(&bindgen_lib_path, &bindings_out)
+            .context("Failed to generate bindings for JVM")?;
+        reformat_kotlin_package(
+            &uniffi_style_identifier,
+            &gen_kt_file_name,
+            &names.package_name,
+            &names.kt_file,
+            &bindings_out,
+        )
+        .context("Failed to reformat generated Kotlin package")?;
+
+        package_natives_jar(&resources_out, &bindings_out.join(JVM_NATIVES_JAR))?;
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir)?;
+
+        Ok(bindings_dest)
+    }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let build_dir = project_dir.join("build");
+        let names = &params.names;
+        let bindings_dest = project_dir.join(&names.bindings_dir);
+        let bindings_out = staging_dir(&build_dir, &names.bindings_dir).join(&names.bindings_dir);
+        let resources_out = bindings_out.join(JVM_RESOURCES_DIR);
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        let mut bindgen_lib_path = None;
+        for &arch in target_archs {
+            let arch_str = arch.as_str();
+            let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+            let out_lib_path = build_dir.join(arch_str).join(mode.as_str()).join(&lib_name);
+            plan.command(
+                format!("Install the {arch_str} target"),
+                &install_arch_command(arch_str),
+                vec![],
+                vec![],
+            );
+            plan.command(
+                format!("Build for {arch_str}"),
+                &cargo_build_lib_command(arch_str, &build_dir, &mode, &params.arch_overrides),
+                vec![project_dir.to_path_buf()],
+                vec![out_lib_path.clone()],
+            );
+            plan.step(
+                format!("Copy the {arch_str} library into the JNA resources"),
+                vec![out_lib_path.clone()],
+                vec![resources_out.join(jna_prefix(arch)).join(&lib_name)],
+            );
+            bindgen_lib_path.get_or_insert(out_lib_path);
+        }
+        let bindgen_lib_path =
+            bindgen_lib_path.context("No target architectures provided for binding generation")?;
+        plan.step(
+            "Generate the Kotlin bindings with UniFFI",
+            vec![bindgen_lib_path],
+            vec![bindings_out
+                .join("uniffi")
+                .join(&names.package_name)
+                .join(&names.kt_file)],
+        );
+        let jar_path = bindings_out.join(JVM_NATIVES_JAR);
+        plan.command(
+            "Package the native libraries",
+            &jar_command(&resources_out, &jar_path),
+            vec![resources_out.clone()],
+            vec![jar_path],
+        );
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
+
+/// Directory JNA searches on the classpath for the current OS and arch,
+/// see `com.sun.jna.Platform.RESOURCE_PREFIX`.
+fn jna_prefix(arch: JvmArch) -> &'static str {
+    match arch.0 {
+        HostArch::X8664Linux => "linux-x86-64",
+        HostArch::Aarch64Linux => "linux-aarch64",
+        HostArch::X8664Darwin => "darwin-x86-64",
+        HostArch::Aarch64Darwin => "darwin-aarch64",
+    }
+}
+
+fn build_for_arch(
+    arch: JvmArch,
+    lib_name: &str,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> anyhow::Result<PathBuf> {
+    let arch_str = arch.as_str();
+    install_arch(arch_str.to_string())?;
+
+    command::run(&mut cargo_build_lib_command(
+        arch_str, build_dir, mode, overrides,
+    ))
+    .context(format!("cargo build failed for {arch_str}"))?;
+
+    Ok(build_dir.join(arch_str).join(mode.as_str()).join(lib_name))
+}
+
+fn generate_jvm_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::Result<()> {
+    generate(GenerateOptions {
+        languages: vec![TargetLanguage::Kotlin],
+        source: Utf8Path::from_path(dylib_path)
+            .ok_or(Error::new(ErrorKind::InvalidInput, "Invalid dylib path"))?
+            .to_path_buf(),
+        out_dir: Utf8Path::from_path(binding_dir)
+            .ok_or(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid kotlin files directory",
+            ))?
+            .to_path_buf(),
+        crate_filter: None,
+        ..GenerateOptions::default()
+    })
+    .map_err(|e| Error::other(e.to_string()))?;
+    Ok(())
+}
+
+fn package_natives_jar(resources_dir: &Path, jar_path: &Path) -> anyhow::Result<()> {
+    command::run(&mut jar_command(resources_dir, jar_path)).with_context(|| {
+        format!(
+            "Failed to package {}, is a JDK installed?",
+            jar_path.display()
+        )
+    })?;
+    Ok(())
+}
+
+fn jar_command(resources_dir: &Path, jar_path: &Path) -> Command {
+    let mut cmd = Command::new("jar");
+    cmd.arg("--create")
+        .arg("--file")
+        .arg(jar_path)
+        .arg("-C")
+        .arg(resources_dir)
+        .arg(".");
+    cmd
+}
@@ -1,29 +1,46 @@
 use anyhow::Context;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
 use toml::Value;
 use uuid::Uuid;
 
 use self::constants::{Arch, Mode, PlatformBuilder, BUILD_MODE_ENV};
+use self::plan::BuildPlan;
 
 #[cfg(feature = "uniffi")]
 pub mod android;
+#[cfg(feature = "uniffi")]
+pub mod c;
+pub mod command;
 pub mod constants;
+pub mod fingerprint;
 #[cfg(feature = "flutter")]
 pub mod flutter;
 #[cfg(feature = "uniffi")]
 pub mod ios;
 #[cfg(feature = "uniffi")]
+pub mod jvm;
+#[cfg(feature = "uniffi")]
+pub mod node;
+pub mod plan;
+#[cfg(feature = "uniffi")]
+pub mod python;
+#[cfg(feature = "uniffi")]
 pub mod react_native;
+pub mod size;
 pub mod web;
 
 /// Stub for feature "uniffi"
 #[cfg(not(feature = "uniffi"))]
 pub mod ios {
     #[inline]
-    pub fn build() {
-        panic!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml");
+    pub fn build() -> anyhow::Result<()> {
+        anyhow::bail!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml")
     }
 }
 
@@ -31,8 +48,8 @@ pub mod ios {
 #[cfg(not(feature = "uniffi"))]
 pub mod android {
     #[inline]
-    pub fn build() {
-        panic!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml");
+    pub fn build() -> anyhow::Result<()> {
+        anyhow::bail!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml")
     }
 }
 
@@ -40,8 +57,8 @@ pub mod android {
 #[cfg(not(feature = "uniffi"))]
 pub mod react_native {
     #[inline]
-    pub fn build() {
-        panic!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml");
+    pub fn build() -> anyhow::Result<()> {
+        anyhow::bail!("\"uniffi\" feature is not enabled, please enable it in your Cargo.toml")
     }
 }
 
@@ -49,30 +66,107 @@ pub mod react_native {
 #[cfg(not(feature = "flutter"))]
 pub mod flutter {
     #[inline]
-    pub fn build() {
-        panic!("\"flutter\" feature is not enabled, please enable it in your Cargo.toml");
+    pub fn build() -> anyhow::Result<()> {
+        anyhow::bail!("\"flutter\" feature is not enabled, please enable it in your Cargo.toml")
+    }
+}
+
+/// Additions to the cargo invocation of one target triple, e.g. a prover
+/// feature enabled only on devices or a custom linker.
+#[derive(Default, Clone, Debug)]
+pub struct ArchBuildConfig {
+    /// Cargo features to enable.
+    pub features: Vec<String>,
+    /// Pass `--no-default-features`.
+    pub no_default_features: bool,
+    /// Extra environment variables for this arch's cargo invocation.
+    pub extra_env: Vec<(String, String)>,
+    /// Flags appended to `RUSTFLAGS` for this arch (e.g. `-Clinker=<wrapper>`).
+    pub extra_rustflags: Vec<String>,
+}
+
+/// [`ArchBuildConfig`]s keyed by target triple ([`Arch::as_str`]).
+pub type ArchOverrides = HashMap<String, ArchBuildConfig>;
+
+impl ArchBuildConfig {
+    /// Add the settings of `other` to these.
+    pub fn extend(&mut self, other: &ArchBuildConfig) {
+        self.features.extend(other.features.iter().cloned());
+        self.no_default_features |= other.no_default_features;
+        self.extra_env.extend(other.extra_env.iter().cloned());
+        self.extra_rustflags
+            .extend(other.extra_rustflags.iter().cloned());
+    }
+
+    /// Apply the features, environment and `RUSTFLAGS` to a cargo command.
+    pub fn apply(&self, cmd: &mut Command) {
+        self.apply_features(cmd);
+        self.apply_env(cmd);
+        self.apply_rustflags(cmd);
+    }
+
+    pub fn apply_features(&self, cmd: &mut Command) {
+        if self.no_default_features {
+            cmd.arg("--no-default-features");
+        }
+        if !self.features.is_empty() {
+            cmd.arg("--features").arg(self.features.join(","));
+        }
+    }
+
+    pub fn apply_env(&self, cmd: &mut Command) {
+        for (key, value) in &self.extra_env {
+            cmd.env(key, value);
+        }
+    }
+
+    /// Append the extra flags to the `RUSTFLAGS` already set on `cmd`, or else
+    /// to the ones of the environment.
+    pub fn apply_rustflags(&self, cmd: &mut Command) {
+        if self.extra_rustflags.is_empty() {
+            return;
+        }
+        let extra = self.extra_rustflags.join(" ");
+        let existing = cmd
+            .get_envs()
+            .find(|(key, _)| *key == "RUSTFLAGS")
+            .and_then(|(_, value)| value)
+            .map(|value| value.to_string_lossy().into_owned())
+            .or_else(|| std::env::var("RUSTFLAGS").ok());
+        let rustflags = match existing {
+            Some(existing) if !existing.trim().is_empty() => format!("{existing} {extra}"),
+            _ => extra,
+        };
+        cmd.env("RUSTFLAGS", rustflags);
+    }
+}
+
+/// Apply the override of `arch`, if any, to a cargo command.
+pub fn apply_arch_override(cmd: &mut Command, overrides: &ArchOverrides, arch: &str) {
+    if let Some(config) = overrides.get(arch) {
+        config.apply(cmd);
     }
 }
 
 /// Builds bindings for the specified platform using environment variables to determine
-/// the build mode, project directory, and target architectures.
-fn build_from_env<Builder: PlatformBuilder>() {
-    let mode = get_build_mode();
+/// the build mode, project directory, and target architectures. Exiting on a
+/// failed build is left to the calling binary.
+fn build_from_env<Builder: PlatformBuilder>() -> anyhow::Result<()> {
+    let mode = get_build_mode()?;
     let project_dir = get_project_dir();
     let target_archs: Vec<Builder::Arch> = get_target_archs();
     let params = Builder::Params::default();
 
     // Do not build if no target architectures are specified
     if target_archs.is_empty() {
-        return;
+        return Ok(());
     }
 
-    Builder::build(mode, &project_dir, target_archs, params)
-        .context(format!(
-            "Failed to build {} bindings",
-            Builder::identifier()
-        ))
-        .unwrap();
+    Builder::build(mode, &project_dir, target_archs, params).context(format!(
+        "Failed to build {} bindings",
+        Builder::identifier()
+    ))?;
+    Ok(())
 }
 
 /// Builds bindings for the specified platform using a string representation of the target architectures.
@@ -100,53 +194,173 @@ pub fn build_from_str_arch<Builder: PlatformBuilder>(
     ))
 }
 
-pub fn mktemp() -> PathBuf {
+/// Describes the build [`build_from_str_arch`] would run with the same arguments.
+pub fn plan_from_str_arch<Builder: PlatformBuilder>(
+    mode: Mode,
+    project_dir: &Path,
+    target_archs: Vec<&String>,
+    params: &Builder::Params,
+) -> anyhow::Result<BuildPlan> {
+    if target_archs.is_empty() {
+        anyhow::bail!(
+            "No target architectures specified for {} bindings",
+            Builder::identifier()
+        );
+    }
+
+    let target_archs: Vec<Builder::Arch> = target_archs
+        .iter()
+        .map(Builder::Arch::parse_from_str)
+        .collect();
+
+    Builder::plan(mode, project_dir, &target_archs, params)
+        .context(format!("Failed to plan {} bindings", Builder::identifier()))
+}
+
+pub fn mktemp() -> anyhow::Result<PathBuf> {
     let dir = std::env::temp_dir().join(Path::new(&Uuid::new_v4().to_string()));
-    fs::create_dir(&dir).expect("Failed to create tmpdir");
-    dir
+    fs::create_dir(&dir).context("Failed to create tmpdir")?;
+    Ok(dir)
 }
 
-fn tmp_local(build_path: &Path) -> PathBuf {
+fn tmp_local(build_path: &Path) -> anyhow::Result<PathBuf> {
     let tmp_path = build_path.join("tmp");
     if let Ok(metadata) = fs::metadata(&tmp_path) {
         if !metadata.is_dir() {
-            panic!("non-directory tmp");
+            anyhow::bail!("{} exists and is not a directory", tmp_path.display());
         }
     } else {
-        fs::create_dir_all(&tmp_path).expect("Failed to create local tmpdir");
+        fs::create_dir_all(&tmp_path).context("Failed to create local tmpdir")?;
     }
-    tmp_path
+    Ok(tmp_path)
 }
 
-pub fn mktemp_local(build_path: &Path) -> PathBuf {
-    let dir = tmp_local(build_path).join(Uuid::new_v4().to_string());
-    fs::create_dir(&dir).expect("Failed to create tmpdir");
-    dir
+/// Directory under `build/tmp/` the bindings in `bindings_dir` are staged in
+/// before [`move_bindings_into_place`]. Plans use the same path as builds.
+pub fn staging_dir(build_path: &Path, bindings_dir: &str) -> PathBuf {
+    build_path.join("tmp").join(bindings_dir)
 }
 
-pub fn cleanup_tmp_local(build_path: &Path) {
-    fs::remove_dir_all(tmp_local(build_path)).expect("Failed to remove tmpdir");
+/// Create an empty [`staging_dir`], removing what an interrupted build left.
+pub fn create_staging_dir(build_path: &Path, bindings_dir: &str) -> anyhow::Result<PathBuf> {
+    tmp_local(build_path)?;
+    let dir = staging_dir(build_path, bindings_dir);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).context("Failed to remove stale staging directory")?;
+    }
+    fs::create_dir(&dir).context("Failed to create staging directory")?;
+    Ok(dir)
+}
+
+pub fn cleanup_tmp_local(build_path: &Path) -> anyhow::Result<()> {
+    fs::remove_dir_all(tmp_local(build_path)?).context("Failed to remove tmpdir")
+}
+
+/// Replace `bindings_dest` with the freshly generated `bindings_out`.
+pub fn move_bindings_into_place(bindings_out: &Path, bindings_dest: &Path) -> anyhow::Result<()> {
+    if let Ok(info) = fs::metadata(bindings_dest) {
+        if !info.is_dir() {
+            anyhow::bail!("{} exists and is not a directory", bindings_dest.display());
+        }
+        fs::remove_dir_all(bindings_dest).context("Failed to remove bindings directory")?;
+    }
+    fs::rename(bindings_out, bindings_dest).context("Failed to move bindings into place")
 }
 
-pub fn install_ndk() {
+/// Install `cargo-ndk` unless it is already available.
+pub fn install_ndk() -> anyhow::Result<()> {
+    if ndk_installed() {
+        return Ok(());
+    }
+    command::run(&mut install_ndk_command()).context("Failed to install cargo-ndk")?;
+    Ok(())
+}
+
+fn ndk_installed() -> bool {
     Command::new("cargo")
-        .arg("install")
-        .arg("cargo-ndk")
-        .spawn()
-        .expect("Failed to spawn cargo, is it installed?")
-        .wait()
-        .expect("Failed to install cargo-ndk");
+        .args(["ndk", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+pub fn install_ndk_command() -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("install").arg("cargo-ndk");
+    cmd
 }
 
-pub fn install_arch(arch: String) {
-    Command::new("rustup")
-        .arg("target")
-        .arg("add")
-        .arg(arch.clone())
-        .spawn()
-        .expect("Failed to spawn rustup, is it installed?")
-        .wait()
-        .unwrap_or_else(|_| panic!("Failed to install target architecture {arch}"));
+pub fn install_arch(arch: String) -> anyhow::Result<()> {
+    command::run(&mut install_arch_command(&arch))
+        .with_context(|| format!("Failed to install target architecture {arch}"))?;
+    Ok(())
+}
+
+/// `cargo build --lib` of the project for `target`, into `build_dir`, with the
+/// override of `target` in `overrides` applied.
+pub fn cargo_build_lib_command(
+    target: &str,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build").arg("--lib").args(mode.cargo_args());
+    apply_arch_override(&mut cmd, overrides, target);
+    cmd.env("CARGO_BUILD_TARGET_DIR", build_dir)
+        .env("CARGO_BUILD_TARGET", target);
+    cmd
+}
+
+/// Run `build` for every arch, up to `jobs` at a time, and return the results
+/// in the order of `archs`. `jobs` of `0` or `1` builds one arch after another.
+pub fn build_archs<A, R, F>(archs: &[A], jobs: usize, build: F) -> anyhow::Result<Vec<R>>
+where
+    A: Arch + Copy + Sync,
+    R: Send,
+    F: Fn(A) -> anyhow::Result<R> + Sync,
+{
+    if jobs <= 1 || archs.len() <= 1 {
+        return archs.iter().map(|&arch| build(arch)).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<anyhow::Result<R>>>> =
+        Mutex::new(archs.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(archs.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(&arch) = archs.get(i) else {
+                    break;
+                };
+                let result = build(arch);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every arch is built"))
+        .collect()
+}
+
+/// Cargo target dir for `arch`. Concurrent cargo builds sharing a target dir
+/// wait on each other's lock, so parallel builds get one per arch.
+pub fn arch_target_dir(build_dir: &Path, arch: &str, jobs: usize) -> PathBuf {
+    if jobs > 1 {
+        build_dir.join("targets").join(arch)
+    } else {
+        build_dir.to_path_buf()
+    }
+}
+
+pub fn install_arch_command(arch: &str) -> Command {
+    let mut cmd = Command::new("rustup");
+    cmd.arg("target").arg("add").arg(arch);
+    cmd
 }
 
 pub fn project_name_from_toml(project_dir: &Path) -> anyhow::Result<String> {
@@ -194,18 +408,38 @@ pub fn raw_project_name_from_toml(project_dir: &Path) -> anyhow::Result<String>
     project_name.ok_or(anyhow::anyhow!("Failed to find project name in Cargo.toml"))
 }
 
+/// Package version from `Cargo.toml`, falling back to `0.1.0` when it is
+/// inherited from a workspace.
+pub fn version_from_toml(project_dir: &Path) -> anyhow::Result<String> {
+    let cargo_toml_path = project_dir.join("Cargo.toml");
+    let cargo_toml_content =
+        fs::read_to_string(cargo_toml_path).context("Failed to read Cargo.toml")?;
+    let cargo_toml: Value = cargo_toml_content
+        .parse::<Value>()
+        .context("Failed to parse Cargo.toml")?;
+
+    let version = cargo_toml
+        .get("package")
+        .and_then(|pkg| pkg.get("version"))
+        .and_then(|version| version.as_str())
+        .unwrap_or("0.1.0");
+
+    Ok(version.to_string())
+}
+
 fn get_project_dir() -> PathBuf {
     std::env::var("CARGO_MANIFEST_DIR")
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("."))
 }
 
-fn get_build_mode() -> Mode {
+fn get_build_mode() -> anyhow::Result<Mode> {
     Mode::parse_from_str(
         std::env::var(BUILD_MODE_ENV)
             .unwrap_or_else(|_| Mode::Debug.as_str().to_string())
             .as_str(),
     )
+    .with_context(|| format!("Invalid {BUILD_MODE_ENV}"))
 }
 
 fn get_target_archs<A: Arch>() -> Vec<A> {
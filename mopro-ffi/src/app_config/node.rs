@@ -0,0 +1,342 @@
+use anyhow::Context;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::constants::{Arch, HostArch, Mode, NodeArch, NodePlatform, NODE_BINDINGS_DIR};
+use super::plan::BuildPlan;
+use super::ArchOverrides;
+use super::{cargo_build_lib_command, install_arch_command, move_bindings_into_place};
+use super::{
+    cleanup_tmp_local, command, create_staging_dir, install_arch, staging_dir, PlatformBuilder,
+};
+use super::{project_name_from_toml, raw_project_name_from_toml, version_from_toml};
+
+/// Crate of the default UniFFI-for-Node generator, also the name of its binary.
+pub const NODE_BINDGEN_CRATE: &str = "uniffi-bindgen-node-js";
+/// Release of [`NODE_BINDGEN_CRATE`] built against the same UniFFI version
+/// (0.31.0) as mopro-ffi.
+pub const NODE_BINDGEN_VERSION: &str = "0.0.16";
+
+pub struct NodeBindingsParams {
+    /// Directory under the project the Node package is written to.
+    pub bindings_dir: String,
+    /// npm package name, the lowercased crate name when unset.
+    pub package_name: Option<String>,
+    pub bindgen: NodeBindgen,
+    /// Per-target-triple overrides, keyed by [`NodeArch::as_str`].
+    pub arch_overrides: ArchOverrides,
+}
+
+impl Default for NodeBindingsParams {
+    fn default() -> Self {
+        Self {
+            bindings_dir: NODE_BINDINGS_DIR.to_string(),
+            package_name: None,
+            bindgen: NodeBindgen::default(),
+            arch_overrides: ArchOverrides::default(),
+        }
+    }
+}
+
+/// UniFFI-for-Node generator, invoked like `uniffi-bindgen-node-js`:
+/// `<command> generate <lib> --out-dir <dir> --package-name <name>`. It must
+/// write an npm package with `package.json`, `index.js` and `index.d.ts` that
+/// loads the library from the package root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeBindgen {
+    /// [`NODE_BINDGEN_CRATE`] or the path of another generator.
+    pub command: String,
+    /// Release of [`NODE_BINDGEN_CRATE`] that must be installed. Ignored for
+    /// other generators.
+    pub version: String,
+}
+
+impl Default for NodeBindgen {
+    fn default() -> Self {
+        Self {
+            command: NODE_BINDGEN_CRATE.to_string(),
+            version: NODE_BINDGEN_VERSION.to_string(),
+        }
+    }
+}
+
+impl PlatformBuilder for NodePlatform {
+    type Arch = NodeArch;
+    type Params = NodeBindingsParams;
+
+    /// Build the host `cdylib` and generate an ESM npm package around it:
+    ///
+    /// ```text
+    /// MoproNodeBindings/
+    /// ├── package.json
+    /// ├── index.js, index.d.ts
+    /// ├── <namespace>.js, <namespace>.d.ts
+    /// ├── <namespace>-ffi.js, <namespace>-ffi.d.ts
+    /// ├── runtime/
+    /// └── lib<name>.so (or .dylib)
+    /// ```
+    ///
+    /// The package depends on `koffi`, installed by `npm install`.
+    fn build(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: Vec<Self::Arch>,
+        params: Self::Params,
+    ) -> anyhow::Result<PathBuf> {
+        // The package ships a single native library, so build one target at a time.
+        let [arch] = target_archs[..] else {
+            anyhow::bail!(
+                "Node bindings are built for a single target, got {}",
+                target_archs.len()
+            );
+        };
+
+        let uniffi_style_identifier = project_name_from_toml(project_dir)
+            .context("Failed to get project name from Cargo.toml")?;
+        let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+
+        // Paths for the generated files
+        let build_dir = project_dir.join("build");
+        let work_dir = create_staging_dir(&build_dir, &params.bindings_dir)?;
+        let bindings_out = work_dir.join(&params.bindings_dir);
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        let package_name = package_name(project_dir, &params)?;
+
+        check_node_bindgen(&params.bindgen)?;
+
+        let lib_path =
+            build_for_arch(arch, &lib_name, &build_dir, &mode, &params.arch_overrides).context(
+                format!("Failed to build for architecture: {}", arch.as_str()),
+            )?;
+
+        command::run(&mut node_bindgen_command(
+            &params.bindgen.command,
+            &lib_path,
+            &bindings_out,
+            &package_name,
+        ))
+        .with_context(|| {
+            format!(
+                "Failed to generate Node bindings with {}",
+                params.bindgen.command
+            )
+        })?;
+        let package_json = bindings_out.join("package.json");
+        if !package_json.exists() {
+            anyhow::bail!(
+                "{} did not generate {}",
+                params.bindgen.command,
+                package_json.display()
+            );
+        }
+        // The generated loader looks for the library at the package root.
+        fs::copy(&lib_path, bindings_out.join(&lib_name))
+            .context("Failed to copy library into the Node package")?;
+
+        let manifest = fs::read_to_string(&package_json).context("Failed to read package.json")?;
+        let manifest = package_json_for_release(&manifest, &version_from_toml(project_dir)?, arch);
+        fs::write(&package_json, manifest).context("Failed to write package.json")?;
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir)?;
+
+        Ok(bindings_dest)
+    }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let [arch] = target_archs[..] else {
+            anyhow::bail!(
+                "Node bindings are built for a single target, got {}",
+                target_archs.len()
+            );
+        };
+        let arch_str = arch.as_str();
+
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+        let build_dir = project_dir.join("build");
+        let lib_path = build_dir.join(arch_str).join(mode.as_str()).join(&lib_name);
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        let bindings_out = staging_dir(&build_dir, &params.bindings_dir).join(&params.bindings_dir);
+        let package_name = package_name(project_dir, params)?;
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        plan.step(
+            format!("Check that {} is installed", params.bindgen.command),
+            vec![],
+            vec![],
+        );
+        plan.command(
+            format!("Install the {arch_str} target"),
+            &install_arch_command(arch_str),
+            vec![],
+            vec![],
+        );
+        plan.command(
+            format!("Build for {arch_str}"),
+            &cargo_build_lib_command(arch_str, &build_dir, &mode, &params.arch_overrides),
+            vec![project_dir.to_path_buf()],
+            vec![lib_path.clone()],
+        );
+        plan.command(
+            "Generate the Node bindings",
+            &node_bindgen_command(
+                &params.bindgen.command,
+                &lib_path,
+                &bindings_out,
+                &package_name,
+            ),
+            vec![lib_path.clone()],
+            vec![
+                bindings_out.join("package.json"),
+                bindings_out.join("index.js"),
+                bindings_out.join("index.d.ts"),
+                bindings_out.join(format!("{uniffi_style_identifier}.js")),
+                bindings_out.join(format!("{uniffi_style_identifier}.d.ts")),
+            ],
+        );
+        plan.step(
+            "Copy the library and set the package version and platform",
+            vec![lib_path, project_dir.join("Cargo.toml")],
+            vec![
+                bindings_out.join(&lib_name),
+                bindings_out.join("package.json"),
+            ],
+        );
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
+
+/// `process.platform` and `process.arch` values of `arch`, used for the
+/// `os` and `cpu` fields of `package.json`.
+fn node_os_cpu(arch: NodeArch) -> (&'static str, &'static str) {
+    match arch.0 {
+        HostArch::X8664Linux => ("linux", "x64"),
+        HostArch::Aarch64Linux => ("linux", "arm64"),
+        HostArch::X8664Darwin => ("darwin", "x64"),
+        HostArch::Aarch64Darwin => ("darwin", "arm64"),
+    }
+}
+
+fn build_for_arch(
+    arch: NodeArch,
+    lib_name: &str,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> anyhow::Result<PathBuf> {
+    let arch_str = arch.as_str();
+    install_arch(arch_str.to_string())?;
+
+    command::run(&mut cargo_build_lib_command(
+        arch_str, build_dir, mode, overrides,
+    ))
+    .context(format!("cargo build failed for {arch_str}"))?;
+
+    Ok(build_dir.join(arch_str).join(mode.as_str()).join(lib_name))
+}
+
+/// npm package name: the configured one, or the lowercased crate name.
+fn package_name(project_dir: &Path, params: &NodeBindingsParams) -> anyhow::Result<String> {
+    match &params.package_name {
+        Some(name) => Ok(name.clone()),
+        None => Ok(raw_project_name_from_toml(project_dir)?.to_lowercase()),
+    }
+}
+
+/// Fail with install instructions unless the generator is available, at the
+/// pinned version for [`NODE_BINDGEN_CRATE`]. Nothing is installed.
+fn check_node_bindgen(bindgen: &NodeBindgen) -> anyhow::Result<()> {
+    if bindgen.command != NODE_BINDGEN_CRATE {
+        if Command::new(&bindgen.command)
+            .arg("--help")
+            .output()
+            .is_err()
+        {
+            anyhow::bail!("Node bindings generator {} not found", bindgen.command);
+        }
+        return Ok(());
+    }
+    let list = Command::new("cargo")
+        .args(["install", "--list"])
+        .output()
+        .context("Failed to list the crates installed with cargo")?;
+    let list = String::from_utf8_lossy(&list.stdout);
+    let installed = installed_version(&list, NODE_BINDGEN_CRATE);
+    if installed != Some(bindgen.version.as_str()) {
+        let found = installed.map_or("it is not installed".to_string(), |v| format!("found {v}"));
+        anyhow::bail!(
+            "{NODE_BINDGEN_CRATE} {version} is required to generate the Node bindings, {found}. \
+            Install it with:\n\n    cargo install {NODE_BINDGEN_CRATE} --version {version} --locked",
+            version = bindgen.version,
+        );
+    }
+    Ok(())
+}
+
+/// Version of `krate` in the output of `cargo install --list`, whose
+/// entries start with a `<crate> v<version>:` line.
+fn installed_version<'a>(list: &'a str, krate: &str) -> Option<&'a str> {
+    list.lines().find_map(|line| {
+        line.strip_prefix(krate)?
+            .strip_prefix(" v")?
+            .split(|c: char| c == ':' || c.is_whitespace())
+            .next()
+    })
+}
+
+fn node_bindgen_command(
+    bindgen: &str,
+    dylib_path: &Path,
+    out_dir: &Path,
+    package_name: &str,
+) -> Command {
+    let mut cmd = Command::new(bindgen);
+    cmd.arg("generate")
+        .arg(dylib_path)
+        .arg("--out-dir")
+        .arg(out_dir)
+        .args(["--package-name", package_name]);
+    cmd
+}
+
+/// Set the crate version and the platform of the native library in the
+/// generated `package.json`, which has a `0.0.0` placeholder version.
+fn package_json_for_release(manifest: &str, version: &str, arch: NodeArch) -> String {
+    let (os, cpu) = node_os_cpu(arch);
+    manifest.replacen(
+        "\"version\": \"0.0.0\",",
+        &format!("\"version\": \"{version}\",\n  \"os\": [\"{os}\"],\n  \"cpu\": [\"{cpu}\"],"),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installed_version() {
+        let list = "cargo-ndk v3.5.4:\n    cargo-ndk\nuniffi-bindgen-node-js v0.0.16:\n    uniffi-bindgen-node-js\n";
+        assert_eq!(installed_version(list, NODE_BINDGEN_CRATE), Some("0.0.16"));
+        assert_eq!(installed_version(list, "cargo"), None);
+        assert_eq!(installed_version("", NODE_BINDGEN_CRATE), None);
+    }
+
+    #[test]
+    fn test_package_json_for_release() {
+        let manifest =
+            "{\n  \"name\": \"mopro\",\n  \"version\": \"0.0.0\",\n  \"type\": \"module\"\n}\n";
+        assert_eq!(
+            package_json_for_release(manifest, "1.2.0", NodeArch(HostArch::X8664Linux)),
+            "{\n  \"name\": \"mopro\",\n  \"version\": \"1.2.0\",\n  \"os\": [\"linux\"],\n  \"cpu\": [\"x64\"],\n  \"type\": \"module\"\n}\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Structured description of what a [`PlatformBuilder`] would run, produced by
+//! [`PlatformBuilder::plan`] without executing anything.
+//!
+//! Paths are the ones the build uses: bindings are staged under `build/tmp/`
+//! (see [`super::staging_dir`]) and moved to [`BuildPlan::output`] last.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::constants::{Arch, Mode, PlatformBuilder};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildPlan {
+    pub platform: String,
+    pub mode: String,
+    pub target_archs: Vec<String>,
+    pub steps: Vec<PlanStep>,
+    /// Directory the bindings are written to.
+    pub output: PathBuf,
+}
+
+/// A single step of a [`BuildPlan`]. Steps done in-process, such as the UniFFI
+/// binding generation, have no `command`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanStep {
+    pub description: String,
+    pub command: Option<PlannedCommand>,
+    pub inputs: Vec<PathBuf>,
+    pub outputs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Variables set on top of the inherited environment.
+    pub env: BTreeMap<String, String>,
+    pub current_dir: Option<PathBuf>,
+}
+
+impl BuildPlan {
+    pub fn new<Builder: PlatformBuilder>(
+        mode: &Mode,
+        target_archs: &[Builder::Arch],
+        output: &Path,
+    ) -> Self {
+        Self {
+            platform: Builder::identifier().to_string(),
+            mode: mode.as_str().to_string(),
+            target_archs: target_archs
+                .iter()
+                .map(|arch| arch.as_str().to_string())
+                .collect(),
+            steps: Vec::new(),
+            output: output.to_path_buf(),
+        }
+    }
+
+    /// Record running `cmd`.
+    pub fn command(
+        &mut self,
+        description: impl Into<String>,
+        cmd: &Command,
+        inputs: Vec<PathBuf>,
+        outputs: Vec<PathBuf>,
+    ) {
+        self.steps.push(PlanStep {
+            description: description.into(),
+            command: Some(PlannedCommand::from(cmd)),
+            inputs,
+            outputs,
+        });
+    }
+
+    /// Record moving the staged `bindings_out` to [`BuildPlan::output`].
+    pub fn move_bindings(&mut self, bindings_out: &Path) {
+        let output = self.output.clone();
+        self.step(
+            "Move the bindings into place",
+            vec![bindings_out.to_path_buf()],
+            vec![output],
+        );
+    }
+
+    /// Record a step mopro does itself.
+    pub fn step(
+        &mut self,
+        description: impl Into<String>,
+        inputs: Vec<PathBuf>,
+        outputs: Vec<PathBuf>,
+    ) {
+        self.steps.push(PlanStep {
+            description: description.into(),
+            command: None,
+            inputs,
+            outputs,
+        });
+    }
+}
+
+impl From<&Command> for PlannedCommand {
+    fn from(cmd: &Command) -> Self {
+        Self {
+            program: cmd.get_program().to_string_lossy().into_owned(),
+            args: cmd
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            env: cmd
+                .get_envs()
+                .filter_map(|(key, value)| {
+                    value.map(|value| {
+                        (
+                            key.to_string_lossy().into_owned(),
+                            value.to_string_lossy().into_owned(),
+                        )
+                    })
+                })
+                .collect(),
+            current_dir: cmd.get_current_dir().map(Path::to_path_buf),
+        }
+    }
+}
+
+/// Shell-like rendering, e.g. `CARGO_BUILD_TARGET=aarch64-apple-ios cargo build --lib`.
+impl fmt::Display for PlannedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.env {
+            write!(f, "{key}={value} ")?;
+        }
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BuildPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({}, {})",
+            self.platform,
+            self.mode,
+            self.target_archs.join(", ")
+        )?;
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "  {}. {}", i + 1, step.description)?;
+            if let Some(command) = &step.command {
+                writeln!(f, "     $ {command}")?;
+                if let Some(dir) = &command.current_dir {
+                    writeln!(f, "     in {}", dir.display())?;
+                }
+            }
+            for input in &step.inputs {
+                writeln!(f, "     < {}", input.display())?;
+            }
+            for output in &step.outputs {
+                writeln!(f, "     > {}", output.display())?;
+            }
+        }
+        write!(f, "  => {}", self.output.display())
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::Context;
+use camino::Utf8Path;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use uniffi::{generate, GenerateOptions, TargetLanguage};
+
+use super::constants::{
+    Arch, Mode, PythonArch, PythonPlatform, PYTHON_BINDINGS_DIR, PYTHON_PACKAGE_NAME,
+};
+use super::plan::BuildPlan;
+use super::ArchOverrides;
+use super::{cargo_build_lib_command, install_arch_command, move_bindings_into_place};
+use super::{
+    cleanup_tmp_local, command, create_staging_dir, install_arch, staging_dir, PlatformBuilder,
+};
+use super::{project_name_from_toml, raw_project_name_from_toml, version_from_toml};
+
+pub struct PythonBindingsParams {
+    /// Directory under the project the Python package is written to.
+    pub bindings_dir: String,
+    /// Import name of the package, also the name of its module file.
+    pub package_name: String,
+    /// Per-target-triple overrides, keyed by [`PythonArch::as_str`].
+    pub arch_overrides: ArchOverrides,
+}
+
+impl Default for PythonBindingsParams {
+    fn default() -> Self {
+        Self {
+            bindings_dir: PYTHON_BINDINGS_DIR.to_string(),
+            package_name: PYTHON_PACKAGE_NAME.to_string(),
+            arch_overrides: ArchOverrides::default(),
+        }
+    }
+}
+
+impl PlatformBuilder for PythonPlatform {
+    type Arch = PythonArch;
+    type Params = PythonBindingsParams;
+
+    /// Build the host `cdylib` and lay it out next to the generated UniFFI
+    /// module as a Python package, ready for `pip install` or `python -m build`:
+    ///
+    /// ```text
+    /// MoproPythonBindings/
+    /// ├── pyproject.toml
+    /// ├── setup.py
+    /// └── <package_name>/
+    ///     ├── __init__.py
+    ///     ├── <package_name>.py
+    ///     └── lib<name>.so (or .dylib)
+    /// ```
+    fn build(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: Vec<Self::Arch>,
+        params: Self::Params,
+    ) -> anyhow::Result<PathBuf> {
+        // A wheel carries a single native library, so build one target at a time.
+        let [arch] = target_archs[..] else {
+            anyhow::bail!(
+                "Python bindings are built for a single target, got {}",
+                target_archs.len()
+            );
+        };
+
+        let uniffi_style_identifier = project_name_from_toml(project_dir)
+            .context("Failed to get project name from Cargo.toml")?;
+
+        // Names for the generated files by uniffi
+        let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+        let gen_python_file_name = format!("{uniffi_style_identifier}.py");
+        let module_file_name = format!("{}.py", params.package_name);
+
+        // Paths for the generated files
+        let build_dir = project_dir.join("build");
+        let work_dir = create_staging_dir(&build_dir, &params.bindings_dir)?;
+        let bindings_out = work_dir.join(&params.bindings_dir);
+        let package_out = bindings_out.join(&params.package_name);
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        fs::create_dir_all(&package_out).context("Failed to create Python package directory")?;
+
+        let lib_path =
+            build_for_arch(arch, &lib_name, &build_dir, &mode, &params.arch_overrides).context(
+                format!("Failed to build for architecture: {}", arch.as_str()),
+            )?;
+
+        generate_python_bindings(&lib_path, &package_out)
+            .context("Failed to generate bindings for Python")?;
+        fs::rename(
+            package_out.join(&gen_python_file_name),
+            package_out.join(&module_file_name),
+        )
+        .context(format!(
+            "Failed to rename bindings from {}/{gen_python_file_name}",
+            package_out.display()
+        ))?;
+
+        // The generated module loads the library from its own directory.
+        fs::copy(&lib_path, package_out.join(&lib_name))
+            .context("Failed to copy library into the Python package")?;
+
+        write_package_files(
+            project_dir,
+            &bindings_out,
+            &package_out,
+            &params.package_name,
+        )?;
+
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir)?;
+
+        Ok(bindings_dest)
+    }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let [arch] = target_archs[..] else {
+            anyhow::bail!(
+                "Python bindings are built for a single target, got {}",
+                target_archs.len()
+            );
+        };
+        let arch_str = arch.as_str();
+
+        let uniffi_style_identifier = project_name_from_toml(project_dir)?;
+        let lib_name = format!("lib{uniffi_style_identifier}.{}", arch.0.lib_extension());
+        let build_dir = project_dir.join("build");
+        let lib_path = build_dir.join(arch_str).join(mode.as_str()).join(&lib_name);
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        let bindings_out = staging_dir(&build_dir, &params.bindings_dir).join(&params.bindings_dir);
+        let package_out = bindings_out.join(&params.package_name);
+
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        plan.command(
+            format!("Install the {arch_str} target"),
+            &install_arch_command(arch_str),
+            vec![],
+            vec![],
+        );
+        plan.command(
+            format!("Build for {arch_str}"),
+            &cargo_build_lib_command(arch_str, &build_dir, &mode, &params.arch_overrides),
+            vec![project_dir.to_path_buf()],
+            vec![lib_path.clone()],
+        );
+        plan.step(
+            "Generate the Python bindings with UniFFI",
+            vec![lib_path],
+            vec![
+                package_out.join(format!("{}.py", params.package_name)),
+                package_out.join(&lib_name),
+            ],
+        );
+        plan.step(
+            "Write the Python package files",
+            vec![project_dir.join("Cargo.toml")],
+            vec![
+                bindings_out.join("pyproject.toml"),
+                bindings_out.join("setup.py"),
+                package_out.join("__init__.py"),
+            ],
+        );
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
+
+fn build_for_arch(
+    arch: PythonArch,
+    lib_name: &str,
+    build_dir: &Path,
+    mode: &Mode,
+    overrides: &ArchOverrides,
+) -> anyhow::Result<PathBuf> {
+    let arch_str = arch.as_str();
+    install_arch(arch_str.to_string())?;
+
+    command::run(&mut cargo_build_lib_command(
+        arch_str, build_dir, mode, overrides,
+    ))
+    .context(format!("cargo build failed for {arch_str}"))?;
+
+    Ok(build_dir.join(arch_str).join(mode.as_str()).join(lib_name))
+}
+
+fn generate_python_bindings(dylib_path: &Path, binding_dir: &Path) -> anyhow::Result<()> {
+    generate(GenerateOptions {
+        languages: vec![TargetLanguage::Python],
+        source: Utf8Path::from_path(dylib_path)
+            .ok_or(Error::new(ErrorKind::InvalidInput, "Invalid dylib path"))?
+            .to_path_buf(),
+        out_dir: Utf8Path::from_path(binding_dir)
+            .ok_or(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid python files directory",
+            ))?
+            .to_path_buf(),
+        crate_filter: None,
+        ..GenerateOptions::default()
+    })
+    .map_err(|e| Error::other(e.to_string()))?;
+    Ok(())
+}
+
+/// Write `pyproject.toml`, `setup.py` and `__init__.py` so the bindings
+/// directory is an installable package.
+fn write_package_files(
+    project_dir: &Path,
+    bindings_out: &Path,
+    package_out: &Path,
+    package_name: &str,
+) -> anyhow::Result<()> {
+    let distribution_name = raw_project_name_from_toml(project_dir)?;
+    let version = version_from_toml(project_dir)?;
+
+    let pyproject = format!(
+        r#"[build-system]
+requires = ["setuptools>=61", "wheel"]
+build-backend = "setuptools.build_meta"
+
+[project]
+name = "{distribution_name}"
+version = "{version}"
+requires-python = ">=3.8"
+
+[tool.setuptools]
+packages = ["{package_name}"]
+
+[tool.setuptools.package-data]
+{package_name} = ["*.so", "*.dylib"]
+"#
+    );
+    // Without ext modules setuptools tags the wheel `py3-none-any`, although it
+    // ships a native library for one platform.
+    let setup_py = r#"from setuptools import setup
+from setuptools.dist import Distribution
+
+
+class BinaryDistribution(Distribution):
+    def has_ext_modules(self):
+        return True
+
+
+setup(distclass=BinaryDistribution)
+"#;
+    let init_py = format!("from .{package_name} import *  # noqa: F401,F403\n");
+
+    fs::write(bindings_out.join("pyproject.toml"), pyproject)
+        .context("Failed to write pyproject.toml")?;
+    fs::write(bindings_out.join("setup.py"), setup_py).context("Failed to write setup.py")?;
+    fs::write(package_out.join("__init__.py"), init_py).context("Failed to write __init__.py")?;
+    Ok(())
+}
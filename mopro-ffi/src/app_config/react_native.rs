@@ -7,28 +7,37 @@ use crate::app_config::constants::{
     Arch, Mode, ReactNativeArch, ReactNativePlatform, REACT_NATIVE_BINDINGS_DIR,
 };
 
-use super::PlatformBuilder;
+use super::plan::BuildPlan;
+use super::{command, ArchBuildConfig, PlatformBuilder};
+
+/// `files` patterns of the native libraries `uniffi-bindgen-react-native`
+/// writes into the turbo module.
+const REACT_NATIVE_XCFRAMEWORK_FILES: &str = "*.xcframework/**";
+const REACT_NATIVE_JNILIBS_FILES: &str = "android/src/main/jniLibs/**";
 
 // Maintained for backwards compatibility
 #[inline]
-pub fn build() {
+pub fn build() -> anyhow::Result<()> {
     super::build_from_env::<ReactNativePlatform>()
 }
 
 impl PlatformBuilder for ReactNativePlatform {
     type Arch = ReactNativeArch;
-    type Params = ();
+    /// Applied to every target; `uniffi-bindgen-react-native` builds the
+    /// targets of a platform in one run and takes no cargo features.
+    type Params = ArchBuildConfig;
 
     fn build(
         mode: Mode,
         project_dir: &Path,
         target_archs: Vec<Self::Arch>,
-        _params: Self::Params,
+        params: Self::Params,
     ) -> anyhow::Result<PathBuf> {
+        check_params(&params)?;
         install_uniffi_bindgen_react_native()?;
 
         fs::create_dir_all(project_dir.join(REACT_NATIVE_BINDINGS_DIR))
-            .expect("failed to create bindings directory");
+            .context("Failed to create bindings directory")?;
 
         // Copy the react_native template to the project directory
         // Get the path to the template directory relative to this source file
@@ -56,9 +65,65 @@ impl PlatformBuilder for ReactNativePlatform {
         fs::write(&target_file, contents)
             .with_context(|| format!("Failed to write ubrn.config.yaml to {:?}", target_file))?;
 
-        generate_react_native_bindings(project_dir, target_archs, mode)?;
+        generate_react_native_bindings(project_dir, target_archs, &mode, &params)?;
         Ok(PathBuf::from(REACT_NATIVE_BINDINGS_DIR))
     }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        check_params(params)?;
+        let bindings_dir = project_dir.join(REACT_NATIVE_BINDINGS_DIR);
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dir);
+        plan.step(
+            "Install uniffi-bindgen-react-native if it is missing",
+            vec![],
+            vec![],
+        );
+        plan.step(
+            "Copy the React Native template and write ubrn.config.yaml",
+            vec![],
+            vec![bindings_dir.join("ubrn.config.yaml")],
+        );
+        plan.command(
+            "Generate the turbo module",
+            &ubrn_generate_command(&bindings_dir),
+            vec![project_dir.to_path_buf()],
+            vec![bindings_dir.clone()],
+        );
+        let platforms = platform_targets(target_archs);
+        for (platform, target_string) in &platforms {
+            plan.command(
+                format!("Build the {platform} bindings"),
+                &ubrn_build_command(platform, &mode, target_string, &bindings_dir, params),
+                vec![project_dir.to_path_buf()],
+                vec![bindings_dir.clone()],
+            );
+        }
+        let built: Vec<&str> = platforms.iter().map(|(platform, _)| *platform).collect();
+        let patterns = missing_files_patterns(&bindings_dir.join("package.json"), &built);
+        if !patterns.is_empty() {
+            plan.command(
+                "Include the native libraries in package.json",
+                &npm_files_command(&bindings_dir, &patterns),
+                vec![bindings_dir.join("package.json")],
+                vec![bindings_dir.join("package.json")],
+            );
+        }
+        Ok(plan)
+    }
+}
+
+fn check_params(params: &ArchBuildConfig) -> anyhow::Result<()> {
+    if !params.features.is_empty() || params.no_default_features {
+        anyhow::bail!(
+            "React Native bindings can't be built with custom cargo features, set them in Cargo.toml instead"
+        );
+    }
+    Ok(())
 }
 
 fn install_uniffi_bindgen_react_native() -> anyhow::Result<()> {
@@ -73,32 +138,24 @@ fn install_uniffi_bindgen_react_native() -> anyhow::Result<()> {
             // Command not found, proceed with installation
             println!("uniffi-bindgen-react-native not found, installing...");
             let current_path: PathBuf = std::env::current_dir()?;
-            let status = Command::new("git")
-                .args([
-                    "clone",
-                    "https://github.com/jhugman/uniffi-bindgen-react-native.git",
-                ])
-                .current_dir(current_path.clone())
-                .status()
-                .expect("failed to download uniffi-bindgen-react-native");
-            if !status.success() {
-                return Err(anyhow::anyhow!(
-                    "Failed to download uniffi-bindgen-react-native"
-                ));
-            }
-
-            let status = Command::new("cargo")
-                .args(["install", "--path", "."])
-                .current_dir(current_path.join("uniffi-bindgen-react-native/crates/ubrn_cli"))
-                .status()
-                .expect("failed to install uniffi-bindgen-react-native");
-            if !status.success() {
-                return Err(anyhow::anyhow!(
-                    "Failed to install uniffi-bindgen-react-native"
-                ));
-            }
+            command::run(
+                Command::new("git")
+                    .args([
+                        "clone",
+                        "https://github.com/jhugman/uniffi-bindgen-react-native.git",
+                    ])
+                    .current_dir(current_path.clone()),
+            )
+            .context("Failed to download uniffi-bindgen-react-native")?;
+
+            command::run(
+                Command::new("cargo")
+                    .args(["install", "--path", "."])
+                    .current_dir(current_path.join("uniffi-bindgen-react-native/crates/ubrn_cli")),
+            )
+            .context("Failed to install uniffi-bindgen-react-native")?;
             fs::remove_dir_all(current_path.join("uniffi-bindgen-react-native"))
-                .expect("failed to remove uniffi-bindgen-react-native");
+                .context("Failed to remove uniffi-bindgen-react-native")?;
         }
         Err(e) => {
             // Other error, propagate it
@@ -115,18 +172,32 @@ fn install_uniffi_bindgen_react_native() -> anyhow::Result<()> {
 fn generate_react_native_bindings(
     project_dir: &Path,
     target_archs: Vec<ReactNativeArch>,
-    mode: Mode,
+    mode: &Mode,
+    params: &ArchBuildConfig,
 ) -> anyhow::Result<()> {
     let bindings_dir = project_dir.join(REACT_NATIVE_BINDINGS_DIR);
-    let status = Command::new("uniffi-bindgen-react-native")
-        .args(["generate", "jsi", "turbo-module"])
-        .current_dir(bindings_dir.clone())
-        .status()
-        .expect("failed to generate react native bindings");
-    if !status.success() {
-        return Err(anyhow::anyhow!("Failed to generate react native bindings"));
+    command::run(&mut ubrn_generate_command(&bindings_dir))
+        .context("Failed to generate react native bindings")?;
+
+    let platforms = platform_targets(&target_archs);
+    for (platform, target_string) in &platforms {
+        build_for_arch(platform, mode, target_string, &bindings_dir, params)?;
     }
 
+    // Include the native libraries in the package.json for mopro-react-native-package
+    let built: Vec<&str> = platforms.iter().map(|(platform, _)| *platform).collect();
+    let patterns = missing_files_patterns(&bindings_dir.join("package.json"), &built);
+    if !patterns.is_empty() {
+        command::run(&mut npm_files_command(&bindings_dir, &patterns))
+            .context("Failed to set files in package.json")?;
+    }
+
+    Ok(())
+}
+
+/// The `uniffi-bindgen-react-native build` platform to run and its
+/// comma-separated targets.
+fn platform_targets(target_archs: &[ReactNativeArch]) -> Vec<(&'static str, String)> {
     let ios_target_string = target_archs
         .iter()
         .filter(|arch| arch.as_str().contains("ios"))
@@ -140,53 +211,122 @@ fn generate_react_native_bindings(
         .collect::<Vec<&str>>()
         .join(",");
 
+    let mut platforms = Vec::new();
     if !ios_target_string.is_empty() {
-        let platform = "ios";
-        build_for_arch(platform, mode, &ios_target_string, &bindings_dir)?;
-    } else if !android_target_string.is_empty() {
-        let platform = "android";
-        build_for_arch(platform, mode, &android_target_string, &bindings_dir)?;
+        platforms.push(("ios", ios_target_string));
     }
-
-    // Include the xcframework in the package.json for mopro-react-native-package
-    let npm_status = Command::new("npm")
-        .args(["pkg", "set", "files[]=*.xcframework/**"])
-        .current_dir(bindings_dir)
-        .status()
-        .expect("failed to set files in package.json");
-    if !npm_status.success() {
-        return Err(anyhow::anyhow!("Failed to set files in package.json"));
+    if !android_target_string.is_empty() {
+        platforms.push(("android", android_target_string));
     }
+    platforms
+}
 
-    Ok(())
+fn ubrn_generate_command(bindings_dir: &Path) -> Command {
+    let mut cmd = Command::new("uniffi-bindgen-react-native");
+    cmd.args(["generate", "jsi", "turbo-module"])
+        .current_dir(bindings_dir);
+    cmd
+}
+
+/// The `files` patterns of the built `platforms` that `package_json` doesn't
+/// list yet. `npm pkg set files[]=` appends, so listed patterns are skipped
+/// to keep rebuilds from duplicating them.
+fn missing_files_patterns(package_json: &Path, platforms: &[&str]) -> Vec<&'static str> {
+    let manifest = fs::read_to_string(package_json).unwrap_or_default();
+    platforms
+        .iter()
+        .filter_map(|platform| match *platform {
+            "ios" => Some(REACT_NATIVE_XCFRAMEWORK_FILES),
+            "android" => Some(REACT_NATIVE_JNILIBS_FILES),
+            _ => None,
+        })
+        .filter(|pattern| !manifest.contains(&format!("\"{pattern}\"")))
+        .collect()
+}
+
+/// Add `patterns` to the `files` of package.json, so the native libraries
+/// are packed with the turbo module.
+fn npm_files_command(bindings_dir: &Path, patterns: &[&str]) -> Command {
+    let mut cmd = Command::new("npm");
+    cmd.args(["pkg", "set"]).current_dir(bindings_dir);
+    for pattern in patterns {
+        cmd.arg(format!("files[]={pattern}"));
+    }
+    cmd
 }
 
 fn build_for_arch(
     platform: &str,
-    mode: Mode,
+    mode: &Mode,
     target_string: &str,
     bindings_dir: &Path,
+    params: &ArchBuildConfig,
 ) -> anyhow::Result<()> {
+    command::run(&mut ubrn_build_command(
+        platform,
+        mode,
+        target_string,
+        bindings_dir,
+        params,
+    ))
+    .context("Failed to build react native bindings")?;
+    Ok(())
+}
+
+fn ubrn_build_command(
+    platform: &str,
+    mode: &Mode,
+    target_string: &str,
+    bindings_dir: &Path,
+    params: &ArchBuildConfig,
+) -> Command {
     let mut args = vec![
         "build".to_string(),
         platform.to_string(),
         "--and-generate".to_string(),
     ];
 
-    if mode == Mode::Release {
-        args.push("--release".to_string());
-    }
+    args.extend(mode.cargo_args().into_iter().map(String::from));
 
     args.push("--targets".to_string());
     args.push(target_string.to_string());
 
-    let status = Command::new("uniffi-bindgen-react-native")
-        .args(&args)
-        .current_dir(bindings_dir)
-        .status()
-        .expect("failed to build react native bindings");
-    if !status.success() {
-        return Err(anyhow::anyhow!("Failed to build react native bindings"));
+    let mut cmd = Command::new("uniffi-bindgen-react-native");
+    cmd.args(&args).current_dir(bindings_dir);
+    params.apply_env(&mut cmd);
+    params.apply_rustflags(&mut cmd);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::mktemp;
+
+    #[test]
+    fn test_missing_files_patterns() {
+        let dir = mktemp().unwrap();
+        let package_json = dir.join("package.json");
+
+        // A fresh template lists neither pattern
+        fs::write(&package_json, r#"{ "files": ["src", "ios"] }"#).unwrap();
+        assert_eq!(
+            missing_files_patterns(&package_json, &["ios", "android"]),
+            vec![REACT_NATIVE_XCFRAMEWORK_FILES, REACT_NATIVE_JNILIBS_FILES]
+        );
+
+        // A rebuild finds the patterns of the previous build
+        fs::write(
+            &package_json,
+            r#"{ "files": ["src", "*.xcframework/**", "android/src/main/jniLibs/**"] }"#,
+        )
+        .unwrap();
+        assert!(missing_files_patterns(&package_json, &["ios", "android"]).is_empty());
+        assert_eq!(
+            missing_files_patterns(&package_json.with_file_name("missing.json"), &["android"]),
+            vec![REACT_NATIVE_JNILIBS_FILES]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
     }
-    Ok(())
 }
@@ -0,0 +1,315 @@
+//! Size report of the libraries a [`PlatformBuilder`](super::PlatformBuilder)
+//! wrote to its bindings directory: the size of each `.a`, `.so`, `.dylib` and
+//! `.wasm`, of the xcframeworks, and optionally the largest symbols, read from
+//! the ELF, Mach-O and wasm objects.
+
+use anyhow::Context;
+use object::read::archive::ArchiveFile;
+use object::{FileKind, Object, ObjectSection, ObjectSymbol, SectionIndex, SymbolKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the libraries included in a report.
+const LIBRARY_EXTENSIONS: [&str; 5] = ["a", "so", "dylib", "dll", "wasm"];
+
+/// Build and dependency directories some bindings (Flutter, React Native)
+/// keep, which are not shipped.
+const SKIPPED_DIRS: [&str; 3] = ["target", "node_modules", "build"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeReport {
+    pub platform: String,
+    pub artifacts: Vec<ArtifactSize>,
+    /// Sum of the library sizes. Xcframeworks are listed but not added, as
+    /// their libraries already are.
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactSize {
+    /// Path relative to the bindings directory.
+    pub path: PathBuf,
+    /// The directory holding the library, e.g. `arm64-v8a` or `ios-arm64`.
+    pub arch: Option<String>,
+    pub size: u64,
+    pub top_symbols: Vec<SymbolSize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolSize {
+    pub name: String,
+    pub size: u64,
+}
+
+impl SizeReport {
+    /// Measure the libraries under `bindings_dir`, listing the `top_symbols`
+    /// largest symbols of each.
+    pub fn collect(
+        platform: &str,
+        bindings_dir: &Path,
+        top_symbols: usize,
+    ) -> anyhow::Result<Self> {
+        let mut report = Self {
+            platform: platform.to_string(),
+            artifacts: Vec::new(),
+            total: 0,
+        };
+        report.collect_dir(bindings_dir, bindings_dir, top_symbols)?;
+        Ok(report)
+    }
+
+    fn collect_dir(&mut self, root: &Path, dir: &Path, top_symbols: usize) -> anyhow::Result<()> {
+        let mut entries = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        for path in entries {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            if path.is_dir() {
+                if path
+                    .file_name()
+                    .is_some_and(|name| SKIPPED_DIRS.iter().any(|dir| name == *dir))
+                {
+                    continue;
+                }
+                if path.extension().is_some_and(|ext| ext == "xcframework") {
+                    self.artifacts.push(ArtifactSize {
+                        path: relative,
+                        arch: None,
+                        size: dir_size(&path)?,
+                        top_symbols: vec![],
+                    });
+                }
+                self.collect_dir(root, &path, top_symbols)?;
+            } else if is_library(&path) {
+                let size = fs::metadata(&path)?.len();
+                let top_symbols = if top_symbols > 0 {
+                    largest_symbols(&path, top_symbols)
+                        .with_context(|| format!("Failed to read symbols of {}", path.display()))?
+                } else {
+                    vec![]
+                };
+                self.total += size;
+                self.artifacts.push(ArtifactSize {
+                    arch: path
+                        .parent()
+                        .filter(|parent| *parent != root)
+                        .and_then(|parent| parent.file_name())
+                        .map(|name| name.to_string_lossy().into_owned()),
+                    path: relative,
+                    size,
+                    top_symbols,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} binary sizes:", self.platform)?;
+        for artifact in &self.artifacts {
+            writeln!(
+                f,
+                "  {:>10}  {}",
+                format_size(artifact.size),
+                artifact.path.display()
+            )?;
+            for symbol in &artifact.top_symbols {
+                writeln!(f, "  {:>10}      {}", format_size(symbol.size), symbol.name)?;
+            }
+        }
+        write!(f, "  {:>10}  total", format_size(self.total))
+    }
+}
+
+/// Human-readable size, e.g. `12.3 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn is_library(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| LIBRARY_EXTENSIONS.iter().any(|lib_ext| ext == *lib_ext))
+}
+
+fn dir_size(dir: &Path) -> anyhow::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        size += if path.is_dir() {
+            dir_size(&path)?
+        } else {
+            fs::metadata(&path)?.len()
+        };
+    }
+    Ok(size)
+}
+
+/// The `count` largest functions and data symbols of a library, summed over
+/// the members of static archives.
+fn largest_symbols(path: &Path, count: usize) -> anyhow::Result<Vec<SymbolSize>> {
+    let data = fs::read(path)?;
+    let mut sizes = HashMap::new();
+    if FileKind::parse(&*data)? == FileKind::Archive {
+        let archive = ArchiveFile::parse(&*data)?;
+        for member in archive.members() {
+            let member_data = member?.data(&*data)?;
+            // Archives also hold non-object members, such as rustc metadata.
+            if let Ok(file) = object::File::parse(member_data) {
+                add_symbol_sizes(&file, &mut sizes);
+            }
+        }
+    } else {
+        add_symbol_sizes(&object::File::parse(&*data)?, &mut sizes);
+    }
+
+    let mut symbols: Vec<SymbolSize> = sizes
+        .into_iter()
+        .map(|(name, size)| SymbolSize { name, size })
+        .collect();
+    symbols.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    symbols.truncate(count);
+    Ok(symbols)
+}
+
+fn add_symbol_sizes(file: &object::File, sizes: &mut HashMap<String, u64>) {
+    let mut by_section: HashMap<SectionIndex, Vec<(u64, u64, String)>> = HashMap::new();
+    for symbol in file.symbols() {
+        if !symbol.is_definition() || !matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+        {
+            continue;
+        }
+        let (Some(section), Ok(name)) = (symbol.section_index(), symbol.name()) else {
+            continue;
+        };
+        by_section.entry(section).or_default().push((
+            symbol.address(),
+            symbol.size(),
+            format!("{:#}", rustc_demangle::demangle(name)),
+        ));
+    }
+
+    // Mach-O symbols carry no size, so take the distance to the next symbol
+    // of the section, or to its end.
+    for (section, mut symbols) in by_section {
+        let section_end = file
+            .section_by_index(section)
+            .map(|section| section.address() + section.size())
+            .unwrap_or(0);
+        symbols.sort_by_key(|(address, _, _)| *address);
+        let next_addresses: Vec<u64> = symbols
+            .iter()
+            .skip(1)
+            .map(|(address, _, _)| *address)
+            .chain(std::iter::once(section_end))
+            .collect();
+        for ((address, size, name), next) in symbols.into_iter().zip(next_addresses) {
+            let size = if size > 0 {
+                size
+            } else {
+                next.saturating_sub(address)
+            };
+            *sizes.entry(name).or_default() += size;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::write::{self, Mangling, StandardSection, Symbol, SymbolSection};
+    use object::{Architecture, BinaryFormat, Endianness, SymbolFlags, SymbolScope};
+
+    fn symbol(
+        name: &str,
+        value: u64,
+        size: u64,
+        kind: SymbolKind,
+        section: SymbolSection,
+    ) -> Symbol {
+        Symbol {
+            name: name.as_bytes().to_vec(),
+            value,
+            size,
+            kind,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section,
+            flags: SymbolFlags::None,
+        }
+    }
+
+    fn symbol_sizes(format: BinaryFormat, symbols: &[(&str, u64, u64)]) -> HashMap<String, u64> {
+        let mut obj = write::Object::new(format, Architecture::Aarch64, Endianness::Little);
+        obj.set_mangling(Mangling::None);
+        let text = obj.section_id(StandardSection::Text);
+        obj.append_section_data(text, &[0; 40], 4);
+        for &(name, value, size) in symbols {
+            let section = SymbolSection::Section(text);
+            obj.add_symbol(symbol(name, value, size, SymbolKind::Text, section));
+        }
+        // Undefined symbols are not part of the library
+        obj.add_symbol(symbol(
+            "imported",
+            0,
+            0,
+            SymbolKind::Text,
+            SymbolSection::Undefined,
+        ));
+
+        let data = obj.write().unwrap();
+        let mut sizes = HashMap::new();
+        add_symbol_sizes(&object::File::parse(&*data).unwrap(), &mut sizes);
+        sizes
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(12_900_000), "12.3 MiB");
+        assert_eq!(format_size(3 << 30), "3.0 GiB");
+        assert_eq!(format_size(2048 << 30), "2048.0 GiB");
+    }
+
+    #[test]
+    fn test_mach_o_symbol_sizes_reach_the_next_symbol() {
+        // Listed out of order, as the symbol table need not be sorted
+        let sizes = symbol_sizes(
+            BinaryFormat::MachO,
+            &[("second", 16, 0), ("first", 0, 0), ("third", 28, 0)],
+        );
+        assert_eq!(sizes.get("first"), Some(&16));
+        assert_eq!(sizes.get("second"), Some(&12));
+        // The last symbol reaches the end of its section
+        assert_eq!(sizes.get("third"), Some(&12));
+        assert!(!sizes.contains_key("imported"));
+    }
+
+    #[test]
+    fn test_elf_symbol_sizes_are_kept() {
+        let sizes = symbol_sizes(BinaryFormat::Elf, &[("first", 0, 8), ("second", 16, 4)]);
+        assert_eq!(sizes.get("first"), Some(&8));
+        assert_eq!(sizes.get("second"), Some(&4));
+    }
+}
@@ -2,91 +2,182 @@ use std::path::Path;
 use std::process::Command;
 use std::{fs, path::PathBuf};
 
-use crate::app_config::cleanup_tmp_local;
-use crate::app_config::constants::{Mode, PlatformBuilder, WebArch, WebPlatform, WEB_BINDINGS_DIR};
+use anyhow::Context;
 
-use super::mktemp_local;
+use crate::app_config::constants::{
+    Arch, Mode, PlatformBuilder, WebArch, WebPlatform, WEB_BINDINGS_DIR,
+};
+use crate::app_config::plan::BuildPlan;
+use crate::app_config::{cleanup_tmp_local, command, move_bindings_into_place};
+
+use super::{create_staging_dir, staging_dir, ArchBuildConfig, ArchOverrides};
+
+/// Dependencies pinned to versions that still build with the wasm toolchain.
+const PATCHED_PACKAGES: [(&str, &str); 4] = [
+    ("indexmap", "2.11.4"),
+    ("backtrace", "0.3.73"),
+    ("blake2b_simd", "1.0.3"),
+    ("wasip2", "1.0.1+wasi-0.2.4"),
+];
 
 // Maintained for backwards compatibility
 #[inline]
-pub fn build() {
+pub fn build() -> anyhow::Result<()> {
     super::build_from_env::<WebPlatform>()
 }
 
+pub struct WebBindingsParams {
+    /// Directory under the project the wasm package is written to.
+    pub bindings_dir: String,
+    /// Override for `wasm32-unknown-unknown`. Its features are enabled on top
+    /// of `wasm`, and default features are always disabled.
+    pub arch_overrides: ArchOverrides,
+}
+
+impl Default for WebBindingsParams {
+    fn default() -> Self {
+        Self {
+            bindings_dir: WEB_BINDINGS_DIR.to_string(),
+            arch_overrides: ArchOverrides::default(),
+        }
+    }
+}
+
 impl PlatformBuilder for WebPlatform {
     type Arch = WebArch;
-    type Params = ();
+    type Params = WebBindingsParams;
 
     fn build(
         mode: Mode,
         project_dir: &Path,
-        _target_archs: Vec<Self::Arch>,
-        _params: Self::Params,
+        target_archs: Vec<Self::Arch>,
+        params: Self::Params,
     ) -> anyhow::Result<PathBuf> {
         if !project_dir.join("Cargo.toml").exists() {
-            panic!("No Cargo.toml found in {:?}", project_dir);
+            anyhow::bail!("No Cargo.toml found in {:?}", project_dir);
         }
         let build_dir_path = project_dir.join("build");
-        let work_dir = mktemp_local(&build_dir_path);
-        let bindings_out = work_dir.join(WEB_BINDINGS_DIR);
-        fs::create_dir(&bindings_out).expect("Failed to create bindings out directory");
-        let bindings_dest = Path::new(&project_dir).join(WEB_BINDINGS_DIR);
-        patch_package_version(project_dir, "indexmap", "2.11.4")?;
-        patch_package_version(project_dir, "backtrace", "0.3.73")?;
-        patch_package_version(project_dir, "blake2b_simd", "1.0.3")?;
-        patch_package_version(project_dir, "wasip2", "1.0.1+wasi-0.2.4")?;
-
-        let mode_cmd = match mode {
-            Mode::Release => "--release",
-            Mode::Debug => "--dev",
-        };
-
-        let mut cmd = Command::new("rustup");
-        cmd.args([
-            "run",
-            "nightly-2025-02-20",
-            "wasm-pack",
-            "build",
-            "--target",
-            "web",
-            mode_cmd,
-            "--out-dir",
-            bindings_out.to_str().unwrap(),
-            "--out-name",
-            "mopro_wasm_lib",
-            "--no-default-features",
-            "--features",
-            "wasm",
-        ]);
-
-        cmd.env(
-            "RUSTFLAGS",
-            "-C target-feature=+atomics,+bulk-memory -C link-arg=--max-memory=4294967296",
-        );
-        cmd.current_dir(project_dir);
+        let work_dir = create_staging_dir(&build_dir_path, &params.bindings_dir)?;
+        let bindings_out = work_dir.join(&params.bindings_dir);
+        fs::create_dir(&bindings_out).context("Failed to create bindings out directory")?;
+        let bindings_dest = Path::new(&project_dir).join(&params.bindings_dir);
+        for (package_name, version) in PATCHED_PACKAGES {
+            patch_package_version(project_dir, package_name, version)?;
+        }
+
+        let overrides = web_overrides(&target_archs, &params);
+        command::run(&mut wasm_pack_command(
+            project_dir,
+            &bindings_out,
+            &mode,
+            &overrides,
+        ))
+        .context("mopro-ffi wasm package build failed")?;
+        println!("mopro-ffi wasm package build completed successfully.");
 
-        let status = cmd.status().expect("Failed to run wasm-pack");
+        move_bindings_into_place(&bindings_out, &bindings_dest)?;
+        cleanup_tmp_local(&build_dir_path)?;
 
-        if status.success() {
-            println!("mopro-ffi wasm package build completed successfully.");
-        } else {
-            eprintln!("mopro-ffi wasm package build failed.");
-            std::process::exit(1);
+        Ok(bindings_dest)
+    }
+
+    fn plan(
+        mode: Mode,
+        project_dir: &Path,
+        target_archs: &[Self::Arch],
+        params: &Self::Params,
+    ) -> anyhow::Result<BuildPlan> {
+        let build_dir_path = project_dir.join("build");
+        let bindings_dest = project_dir.join(&params.bindings_dir);
+        let bindings_out =
+            staging_dir(&build_dir_path, &params.bindings_dir).join(&params.bindings_dir);
+        let mut plan = BuildPlan::new::<Self>(&mode, target_archs, &bindings_dest);
+        for (package_name, version) in PATCHED_PACKAGES {
+            plan.command(
+                format!("Pin {package_name} to {version}"),
+                &patch_package_command(project_dir, package_name, version),
+                vec![project_dir.join("Cargo.lock")],
+                vec![project_dir.join("Cargo.lock")],
+            );
         }
+        plan.command(
+            "Build the wasm package",
+            &wasm_pack_command(
+                project_dir,
+                &bindings_out,
+                &mode,
+                &web_overrides(target_archs, params),
+            ),
+            vec![project_dir.to_path_buf()],
+            vec![bindings_out.join("mopro_wasm_lib.js")],
+        );
+        plan.move_bindings(&bindings_out);
+        Ok(plan)
+    }
+}
 
-        if let Ok(info) = fs::metadata(&bindings_dest) {
-            if !info.is_dir() {
-                panic!("framework directory exists and is not a directory");
-            }
-            fs::remove_dir_all(&bindings_dest).expect("Failed to remove framework directory");
+/// The overrides of all `target_archs` (there is only `wasm32-unknown-unknown`).
+fn web_overrides(target_archs: &[WebArch], params: &WebBindingsParams) -> ArchBuildConfig {
+    let mut overrides = ArchBuildConfig::default();
+    for arch in target_archs {
+        if let Some(config) = params.arch_overrides.get(arch.as_str()) {
+            overrides.extend(config);
         }
+    }
+    overrides
+}
 
-        fs::rename(&bindings_out, &bindings_dest).expect("Failed to move framework into place");
+fn wasm_pack_command(
+    project_dir: &Path,
+    bindings_out: &Path,
+    mode: &Mode,
+    overrides: &ArchBuildConfig,
+) -> Command {
+    let features = std::iter::once("wasm")
+        .chain(overrides.features.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(",");
 
-        cleanup_tmp_local(&build_dir_path);
+    let mode_args = match mode {
+        Mode::Debug => vec!["--dev"],
+        mode => mode.cargo_args(),
+    };
 
-        Ok(bindings_dest)
-    }
+    let mut cmd = Command::new("rustup");
+    cmd.args([
+        "run",
+        "nightly-2025-02-20",
+        "wasm-pack",
+        "build",
+        "--target",
+        "web",
+    ])
+    .args(mode_args)
+    .arg("--out-dir")
+    .arg(bindings_out)
+    .args([
+        "--out-name",
+        "mopro_wasm_lib",
+        "--no-default-features",
+        "--features",
+    ])
+    .arg(features);
+
+    cmd.env(
+        "RUSTFLAGS",
+        "-C target-feature=+atomics,+bulk-memory -C link-arg=--max-memory=4294967296",
+    );
+    overrides.apply_env(&mut cmd);
+    overrides.apply_rustflags(&mut cmd);
+    cmd.current_dir(project_dir);
+    cmd
+}
+
+fn patch_package_command(project_dir: &Path, package_name: &str, version: &str) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(["update", "-p", package_name, "--precise", version]);
+    cmd.current_dir(project_dir);
+    cmd
 }
 
 fn patch_package_version(
@@ -94,13 +185,53 @@ fn patch_package_version(
     package_name: &str,
     version: &str,
 ) -> anyhow::Result<()> {
-    let mut cmd = Command::new("cargo");
-    cmd.args(["update", "-p", package_name, "--precise", version]);
-    cmd.current_dir(project_dir);
-    let status = cmd.status().expect("Failed to update package");
-    if !status.success() {
-        eprintln!("Failed to update package");
-        return Err(anyhow::anyhow!("Failed to update package"));
-    }
+    command::run(&mut patch_package_command(
+        project_dir,
+        package_name,
+        version,
+    ))
+    .with_context(|| format!("Failed to update package {package_name}"))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_matches_build_paths() {
+        let project_dir = Path::new("/project");
+        let plan = WebPlatform::plan(
+            Mode::Release,
+            project_dir,
+            &[WebArch],
+            &WebBindingsParams::default(),
+        )
+        .unwrap();
+
+        let staged = project_dir
+            .join("build/tmp")
+            .join(WEB_BINDINGS_DIR)
+            .join(WEB_BINDINGS_DIR);
+        let wasm_pack = plan
+            .steps
+            .iter()
+            .find_map(|step| {
+                step.command
+                    .as_ref()
+                    .filter(|cmd| cmd.args.iter().any(|arg| arg == "wasm-pack"))
+            })
+            .unwrap();
+        let out_dir = wasm_pack
+            .args
+            .iter()
+            .position(|arg| arg == "--out-dir")
+            .map(|i| &wasm_pack.args[i + 1])
+            .unwrap();
+        assert_eq!(Path::new(out_dir), staged);
+
+        let last = plan.steps.last().unwrap();
+        assert_eq!(last.inputs, vec![staged]);
+        assert_eq!(last.outputs, vec![project_dir.join(WEB_BINDINGS_DIR)]);
+    }
+}
@@ -1,3 +1,3 @@
-fn main() {
-    mopro_ffi::app_config::ios::build();
+fn main() -> anyhow::Result<()> {
+    mopro_ffi::app_config::ios::build()
 }